@@ -1,119 +1,751 @@
-use polars::prelude::{DataType, Field, TimeUnit};
+use std::str::FromStr;
+
+use polars::export::chrono::FixedOffset;
+#[cfg(feature = "dtype-struct")]
+use polars::prelude::Field;
+use polars::prelude::{DataType, TimeUnit};
 use proc_macro2::TokenStream;
-use syn::{parse::Parse, parse_quote, spanned::Spanned, Ident, ItemStruct, Token};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    ext::IdentExt, parse::Parse, parse_quote, spanned::Spanned, Ident, ItemEnum, ItemStruct, Token,
+};
+
+use crate::rename::RenameRule;
+
+/// The item that a derive macro was applied to.
+pub enum Input {
+    Struct(Template),
+    Enum(EnumTemplate),
+}
+
+impl Parse for Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        match input.parse()? {
+            syn::Item::Struct(structure) => Ok(Self::Struct(Template::new(structure)?)),
+            syn::Item::Enum(enumeration) => Ok(Self::Enum(EnumTemplate::new(enumeration)?)),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "only structs and enums are supported",
+            )),
+        }
+    }
+}
 
 pub struct Template {
     pub fields: Vec<Column>,
     pub structure: ItemStruct,
+    /// if set, these bounds are used instead of the ones which are inferred
+    /// from the types of the fields
+    pub bound: Option<Vec<syn::WherePredicate>>,
+    /// if set, the struct has a single field and is stored in the same way as
+    /// the type of that field
+    pub transparent: bool,
 }
 
-impl Parse for Template {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let structure: ItemStruct = input.parse()?;
-        if structure.generics.type_params().count() > 0 {
-            return Err(syn::Error::new(
-                structure.generics.span(),
-                "generic parameters are not allowed",
-            ));
+impl Template {
+    fn new(structure: ItemStruct) -> syn::Result<Self> {
+        let mut rename_all = None;
+        let mut bound = None;
+        let mut transparent = false;
+
+        for attr in &structure.attrs {
+            if !attr.path.is_ident("df") {
+                continue;
+            }
+
+            let opts: ContainerAttr = attr.parse_args()?;
+
+            for opt in opts.0 {
+                match opt {
+                    // the columns of tuple structs are named after the
+                    // position of their fields, so there is nothing to rename
+                    ContainerAttrOption::RenameAll(_)
+                        if matches!(structure.fields, syn::Fields::Unnamed(_)) =>
+                    {
+                        return Err(syn::Error::new(
+                            attr.span(),
+                            "rename_all cannot be used on tuple structs",
+                        ))
+                    }
+                    ContainerAttrOption::RenameAll(rule) => rename_all = Some(rule),
+                    ContainerAttrOption::Bound(predicates) => bound = Some(predicates),
+                    ContainerAttrOption::Transparent => transparent = true,
+                    ContainerAttrOption::Categorical(_) | ContainerAttrOption::Tag(_) => {
+                        return Err(syn::Error::new(
+                            attr.span(),
+                            "only rename_all, bound and transparent are supported on structs",
+                        ))
+                    }
+                }
+            }
+        }
+
+        if transparent {
+            if structure.fields.len() != 1 {
+                return Err(syn::Error::new(
+                    structure.ident.span(),
+                    "transparent structs must have exactly one field",
+                ));
+            }
+
+            if let Some(attr) = structure
+                .fields
+                .iter()
+                .flat_map(|field| &field.attrs)
+                .find(|attr| attr.path.is_ident("df"))
+            {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "the field of a transparent struct cannot have attributes",
+                ));
+            }
         }
 
-        let mut cols = vec![];
+        Ok(Self {
+            fields: parse_fields(&structure.fields, rename_all)?,
+            structure,
+            bound,
+            transparent,
+        })
+    }
+}
 
-        for (idx, field) in structure.fields.iter().enumerate() {
-            let mut dtype = None;
-            let mut convert_into = None;
-            let mut convert_from = None;
-            let mut optional = false;
+/// Reads the columns out of the fields of a struct or an enum variant.
+fn parse_fields(fields: &syn::Fields, rename_all: Option<RenameRule>) -> syn::Result<Vec<Column>> {
+    let mut cols = vec![];
 
-            for attr in &field.attrs {
-                if !attr.path.is_ident("df") {
-                    continue;
+    for (idx, field) in fields.iter().enumerate() {
+        let mut dtype = None;
+        let mut convert_into = None;
+        let mut convert_from = None;
+        let mut optional = false;
+        let mut flatten = false;
+        let mut prefix = None;
+        let mut rename = None;
+        let mut aliases = vec![];
+        let mut skip_into = false;
+        let mut skip_from = false;
+        let mut default = None;
+        let mut timezone = None;
+        let mut time_unit = None;
+
+        for attr in &field.attrs {
+            if !attr.path.is_ident("df") {
+                continue;
+            }
+
+            let opts: Attr = attr.parse_args()?;
+
+            for opt in opts.0 {
+                match opt {
+                    AttrOption::Into(ty) => convert_into = Some(ConvertInto::Into(ty)),
+                    AttrOption::TryInto(ty) => convert_into = Some(ConvertInto::TryInto(ty)),
+                    AttrOption::AsRef(ty) => convert_into = Some(ConvertInto::AsRef(ty)),
+                    AttrOption::Dtype(dt) => dtype = Some(dt),
+                    AttrOption::Optional(opt) => optional = opt,
+                    AttrOption::TryFrom { borrow } => {
+                        convert_from = Some(ConvertFrom::TryFrom { borrow })
+                    }
+                    AttrOption::SerializeWith {
+                        fun,
+                        borrow,
+                        fallible,
+                    } => {
+                        convert_into = Some(ConvertInto::Custom {
+                            fun,
+                            borrow,
+                            fallible,
+                        })
+                    }
+                    AttrOption::DeserializeWith { fun, borrow } => {
+                        convert_from = Some(ConvertFrom::Custom { fun, borrow })
+                    }
+                    AttrOption::Flatten => flatten = true,
+                    AttrOption::Prefix(p) => prefix = Some(p),
+                    AttrOption::Alias(alias) => aliases.push(alias),
+                    AttrOption::Rename(name) => rename = Some(name.value()),
+                    AttrOption::Skip => {
+                        skip_into = true;
+                        skip_from = true;
+                    }
+                    AttrOption::SkipSerializing => skip_into = true,
+                    AttrOption::SkipDeserializing => skip_from = true,
+                    AttrOption::Default(value) => default = Some(value),
+                    AttrOption::TimeZone(tz) => timezone = Some(tz),
+                    AttrOption::TimeUnit(tu) => time_unit = Some(tu),
                 }
+            }
+        }
 
-                let opts: Attr = attr.parse_args()?;
+        // the Rust type of the data in this column, after it has been
+        // converted and with the Option stripped off
+        let mut rtype = None;
 
-                for opt in opts.0 {
-                    match opt {
-                        AttrOption::Into(ty) => convert_into = Some(ConvertInto::Into(ty)),
-                        AttrOption::AsRef(ty) => convert_into = Some(ConvertInto::AsRef(ty)),
-                        AttrOption::Dtype(dt) => dtype = Some(dt),
-                        AttrOption::Optional(opt) => optional = opt,
-                        AttrOption::TryFrom { borrow } => {
-                            convert_from = Some(ConvertFrom::TryFrom { borrow })
-                        }
-                        AttrOption::SerializeWith { fun, borrow } => {
-                            convert_into = Some(ConvertInto::Custom { fun, borrow })
-                        }
-                        AttrOption::DeserializeWith { fun, borrow } => {
-                            convert_from = Some(ConvertFrom::Custom { fun, borrow })
-                        }
+        if skip_into && skip_from {
+            // this field is never stored in a column, so its type does not
+            // need to be supported by Polars
+            rtype = Some(field.ty.clone());
+        }
+
+        if dtype.is_none() && rtype.is_none() {
+            if let Some(convert_into) = &convert_into {
+                match convert_into {
+                    ConvertInto::Into(ty) | ConvertInto::TryInto(ty) | ConvertInto::AsRef(ty) => {
+                        let (dt, rt, opt) = dtype_for_rtype_opt(ty)?;
+                        dtype = dt;
+                        rtype = Some(rt.clone());
+                        optional = opt;
+                    }
+                    ConvertInto::Custom { .. } => {
+                        // dtype must be specified explicitly if using a
+                        // custom conversion function
                     }
                 }
             }
+        }
 
-            if dtype.is_none() {
-                if let Some(convert_into) = &convert_into {
-                    match convert_into {
-                        ConvertInto::Into(ty) | ConvertInto::AsRef(ty) => {
-                            let (dt, opt) = dtype_for_rtype_opt(ty)?;
-                            dtype = Some(dt);
-                            optional = opt;
-                        }
-                        ConvertInto::Custom { .. } => {
-                            // dtype must be specified explicitly if using a
-                            // custom conversion function
+        // an explicit temporal data type only changes how the values are
+        // stored, so the type of the field is kept, and so is its time zone
+        if let (Some(dtype), None, None) = (&mut dtype, &rtype, &convert_into) {
+            if let Ok((Some(inferred), ty, _)) = dtype_for_rtype_opt(&field.ty) {
+                match (dtype, inferred) {
+                    (DataType::Datetime(_, zone), DataType::Datetime(_, inferred)) => {
+                        if zone.is_none() {
+                            *zone = inferred;
                         }
+                        rtype = Some(ty.clone());
                     }
+                    (DataType::Date, DataType::Date)
+                    | (DataType::Time, DataType::Time)
+                    | (DataType::Duration(_), DataType::Duration(_)) => rtype = Some(ty.clone()),
+                    _ => {}
                 }
             }
+        }
 
-            if dtype.is_none() {
-                let (dt, opt) = dtype_for_rtype_opt(&field.ty)?;
-                dtype = Some(dt);
-                optional = opt;
-            }
+        if dtype.is_none() && rtype.is_none() {
+            let (dt, rt, opt) = dtype_for_rtype_opt(&field.ty)?;
+            dtype = dt;
+            rtype = Some(rt.clone());
+            optional = opt;
+        }
 
-            // if we are not using a custom conversion function for Datetime, use our helper by default
-            if let (Some(DataType::Datetime(_, _)), None) = (&dtype, &convert_from) {
-                convert_from = Some(ConvertFrom::Custom {
-                    fun: parse_quote! { ::polars_derive::helpers::deserialize_datetime },
-                    borrow: false,
-                })
+        let rtype = match (rtype, &dtype) {
+            (Some(rtype), _) => rtype,
+            (None, Some(dtype)) => rtype_for_dtype(dtype),
+            (None, None) => unreachable!(),
+        };
+
+        if let Some(tz) = timezone {
+            // the type of the values which are written, after the conversion
+            let written = match &convert_into {
+                Some(ConvertInto::Into(ty) | ConvertInto::TryInto(ty) | ConvertInto::AsRef(ty)) => {
+                    ty
+                }
+                Some(ConvertInto::Custom { .. }) => &rtype,
+                None => &field.ty,
+            };
+            check_timezone(&tz, strip_option(written)?.0)?;
+
+            match dtype.as_mut().and_then(datetime_dtype) {
+                Some(DataType::Datetime(_, zone)) => *zone = Some(tz.value()),
+                _ => {
+                    return Err(syn::Error::new(
+                        tz.span(),
+                        "timezone can only be used on datetime fields",
+                    ))
+                }
             }
+        }
 
-            cols.push(Column {
-                span: field.span(),
-                name: field
-                    .ident
-                    .as_ref()
-                    .map(|id| id.to_string())
-                    .unwrap_or_else(|| idx.to_string()),
-                ty: field.ty.clone(),
-                dtype: dtype.unwrap(),
-                convert_into,
-                convert_from,
-                optional,
-            })
+        if let Some((unit, span)) = time_unit {
+            match dtype.as_mut().and_then(dtype_time_unit) {
+                Some(tu) => *tu = unit,
+                None => {
+                    return Err(syn::Error::new(
+                        span,
+                        "time_unit can only be used on datetime and duration fields",
+                    ))
+                }
+            }
         }
 
-        Ok(Self {
-            fields: cols,
-            structure,
+        let flatten = match (flatten, prefix) {
+            (false, None) => None,
+            (false, Some(prefix)) => {
+                return Err(syn::Error::new(
+                    prefix.span(),
+                    "prefix can only be used together with flatten",
+                ))
+            }
+            (true, prefix) => {
+                if dtype.is_some() || convert_into.is_some() || convert_from.is_some() {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "only nested structures without conversions can be flattened",
+                    ));
+                }
+
+                if optional {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "optional fields cannot be flattened",
+                    ));
+                }
+
+                if let Some(alias) = aliases.first() {
+                    return Err(syn::Error::new(
+                        alias.span(),
+                        "flattened fields cannot have aliases",
+                    ));
+                }
+
+                if let Some(value) = &default {
+                    return Err(syn::Error::new(
+                        value.span(field),
+                        "flattened fields cannot have defaults",
+                    ));
+                }
+
+                Some(prefix.map(|p| p.value()).unwrap_or_default())
+            }
+        };
+
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(idx.into()),
+        };
+
+        // fields of tuple structs are named after their position, see
+        // Template::new
+        let name = rename.unwrap_or_else(|| match &field.ident {
+            Some(ident) => {
+                let name = ident.unraw().to_string();
+                match rename_all {
+                    Some(rule) => rule.apply_to_field(&name),
+                    None => name,
+                }
+            }
+            None => format!("column_{idx}"),
+        });
+
+        cols.push(Column {
+            span: field.span(),
+            name,
+            member,
+            ty: field.ty.clone(),
+            rtype,
+            dtype,
+            convert_into,
+            convert_from,
+            optional,
+            flatten,
+            aliases: aliases.iter().map(|alias| alias.value()).collect(),
+            skip_into,
+            skip_from,
+            default,
         })
     }
+
+    Ok(cols)
 }
 
+#[derive(Clone)]
 pub struct Column {
+    /// the name of the column in the `DataFrame`
     pub name: String,
+    /// the field of the struct or enum variant that holds the data
+    pub member: syn::Member,
     pub span: proc_macro2::Span,
 
     pub ty: syn::Type,
-    pub dtype: DataType,
+    /// the type of the data in this column as it is passed to Polars, without
+    /// the `Option` if the column is optional
+    pub rtype: syn::Type,
+    /// `None` if this column holds a nested structure, whose data type is only
+    /// known at runtime via its `IntoColumn` implementation
+    pub dtype: Option<DataType>,
     pub optional: bool,
     /// mechanism to use when converting from user struct into Polars format
     pub convert_into: Option<ConvertInto>,
     /// mechanism to use when converting from Polars format into user struct
     pub convert_from: Option<ConvertFrom>,
+    /// if set, this field is a nested structure whose columns are inlined into
+    /// the parent, with this prefix prepended to their names
+    pub flatten: Option<String>,
+    /// other names of the column, which are tried in order when reading a
+    /// `DataFrame` that does not have a column with the actual name
+    pub aliases: Vec<String>,
+    /// whether this field is left out when converting into a `DataFrame`
+    pub skip_into: bool,
+    /// whether this field is left out when converting from a `DataFrame`, in
+    /// which case it is filled with its default value
+    pub skip_from: bool,
+    /// the value to use if the column is missing or holds a null
+    pub default: Option<DefaultValue>,
+}
+
+#[derive(Clone)]
+pub enum DefaultValue {
+    /// use the `Default` implementation of the field's type
+    Trait,
+    /// call a user-supplied function
+    Path(syn::ExprPath),
+}
+
+impl DefaultValue {
+    fn span(&self, field: &syn::Field) -> proc_macro2::Span {
+        match self {
+            Self::Trait => field.span(),
+            Self::Path(path) => path.span(),
+        }
+    }
+
+    pub fn to_expr(&self) -> TokenStream {
+        match self {
+            Self::Trait => quote! { ::std::default::Default::default() },
+            Self::Path(path) => quote! { #path() },
+        }
+    }
+
+    /// Returns a string which identifies this default value, so that the
+    /// defaults of two fields can be compared.
+    fn key(&self) -> String {
+        self.to_expr().to_string()
+    }
+}
+
+impl Column {
+    /// Returns Rust code which evaluates to the default value of this field.
+    pub fn default_expr(&self) -> TokenStream {
+        self.default
+            .as_ref()
+            .unwrap_or(&DefaultValue::Trait)
+            .to_expr()
+    }
+
+    /// Returns true if the data for this field is borrowed from the
+    /// `DataFrame` (ex.: `&'a str`) instead of being copied out of it.
+    pub fn borrows(&self) -> bool {
+        matches!(self.rtype, syn::Type::Reference(_))
+    }
+
+    /// Returns an identifier for a local variable related to this field, which
+    /// is derived from the field instead of the column name b/c the column
+    /// name might not be a valid identifier.
+    pub fn var(&self, prefix: &str) -> Ident {
+        match &self.member {
+            syn::Member::Named(ident) => format_ident!("{}_{}", prefix, ident.unraw()),
+            syn::Member::Unnamed(idx) => format_ident!("{}_{}", prefix, idx.index),
+        }
+    }
+}
+
+/// An enum. If none of its variants have fields, it is stored in a single
+/// column containing the names of its variants. Otherwise, each row is stored
+/// as the name of its variant in the tag column, followed by the union of the
+/// columns of all variants.
+pub struct EnumTemplate {
+    pub variants: Vec<Variant>,
+    pub enumeration: ItemEnum,
+    /// whether the names of the variants are stored as Categorical instead of
+    /// Utf8
+    pub categorical: bool,
+    /// the name of the column containing the names of the variants
+    pub tag: String,
+    /// the union of the columns of all variants, in order of appearance
+    pub columns: Vec<Column>,
+    /// same as [`Template::bound`]
+    pub bound: Option<Vec<syn::WherePredicate>>,
+}
+
+pub struct Variant {
+    pub ident: Ident,
+    /// the name of the variant as it is stored in the column
+    pub name: String,
+    pub fields: Vec<Column>,
+}
+
+impl EnumTemplate {
+    /// Whether this enum has any variants with fields, in which case it is
+    /// stored as a `DataFrame` instead of as a single column.
+    pub fn is_tagged(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| !variant.fields.is_empty())
+    }
+}
+
+impl EnumTemplate {
+    fn new(enumeration: ItemEnum) -> syn::Result<Self> {
+        let mut rename_all = None;
+        let mut categorical = false;
+        let mut tag = None;
+        let mut bound = None;
+
+        for attr in &enumeration.attrs {
+            if !attr.path.is_ident("df") {
+                continue;
+            }
+
+            let opts: ContainerAttr = attr.parse_args()?;
+
+            for opt in opts.0 {
+                match opt {
+                    ContainerAttrOption::RenameAll(rule) => rename_all = Some(rule),
+                    ContainerAttrOption::Categorical(cat) => categorical = cat,
+                    ContainerAttrOption::Tag(name) => tag = Some(name),
+                    ContainerAttrOption::Bound(predicates) => bound = Some(predicates),
+                    ContainerAttrOption::Transparent => {
+                        return Err(syn::Error::new(
+                            attr.span(),
+                            "only structs can be transparent",
+                        ))
+                    }
+                }
+            }
+        }
+
+        let mut variants = vec![];
+        let mut columns: Vec<Column> = vec![];
+
+        for variant in &enumeration.variants {
+            if let syn::Fields::Unnamed(fields) = &variant.fields {
+                return Err(syn::Error::new(
+                    fields.span(),
+                    "only enum variants with named fields are supported",
+                ));
+            }
+
+            let fields = parse_fields(&variant.fields, None)?;
+
+            for field in &fields {
+                if field.flatten.is_some() {
+                    return Err(syn::Error::new(
+                        field.span,
+                        "fields of enum variants cannot be flattened",
+                    ));
+                }
+
+                if field.skip_into && field.skip_from {
+                    continue;
+                }
+
+                // variants can share columns, as long as the types match
+                match columns.iter().find(|col| col.name == field.name) {
+                    Some(col) => {
+                        if col.ty.to_token_stream().to_string()
+                            != field.ty.to_token_stream().to_string()
+                            || col.dtype != field.dtype
+                            || col.convert_into.as_ref().map(ConvertInto::key)
+                                != field.convert_into.as_ref().map(ConvertInto::key)
+                            || col.convert_from.as_ref().map(ConvertFrom::key)
+                                != field.convert_from.as_ref().map(ConvertFrom::key)
+                            || col.aliases != field.aliases
+                            || col.skip_into != field.skip_into
+                            || col.skip_from != field.skip_from
+                            || col.default.as_ref().map(DefaultValue::key)
+                                != field.default.as_ref().map(DefaultValue::key)
+                        {
+                            return Err(syn::Error::new(
+                                field.span,
+                                format!(
+                                    "field {} has a different type or different attributes in \
+                                     another variant",
+                                    field.name
+                                ),
+                            ));
+                        }
+                    }
+                    None => columns.push(field.clone()),
+                }
+            }
+
+            let mut name = None;
+
+            for attr in &variant.attrs {
+                if !attr.path.is_ident("df") {
+                    continue;
+                }
+
+                let opts: Attr = attr.parse_args()?;
+
+                for opt in opts.0 {
+                    match opt {
+                        AttrOption::Rename(rename) => name = Some(rename.value()),
+                        _ => {
+                            return Err(syn::Error::new(
+                                attr.span(),
+                                "only rename is supported on enum variants",
+                            ))
+                        }
+                    }
+                }
+            }
+
+            let name = name.unwrap_or_else(|| {
+                let ident = variant.ident.to_string();
+                match rename_all {
+                    Some(rule) => rule.apply_to_variant(&ident),
+                    None => ident,
+                }
+            });
+
+            variants.push(Variant {
+                ident: variant.ident.clone(),
+                name,
+                fields,
+            });
+        }
+
+        let tag = match tag {
+            Some(tag) if variants.iter().all(|variant| variant.fields.is_empty()) => {
+                return Err(syn::Error::new(
+                    tag.span(),
+                    "tag can only be used on enums whose variants have fields",
+                ))
+            }
+            Some(tag) => tag.value(),
+            None => "type".to_owned(),
+        };
+
+        if columns.iter().any(|col| col.name == tag) {
+            return Err(syn::Error::new(
+                enumeration.ident.span(),
+                format!("the tag column {tag} has the same name as a field"),
+            ));
+        }
+
+        Ok(Self {
+            variants,
+            enumeration,
+            categorical,
+            tag,
+            columns,
+            bound,
+        })
+    }
+}
+
+/// Returns a copy of `generics` for an impl of one of the derived traits. Each
+/// field whose type mentions a type parameter gets the bound returned by
+/// `field_bound`, unless the bounds were given explicitly using `bound`.
+pub fn bounded_generics<'a>(
+    generics: &syn::Generics,
+    bound: Option<&[syn::WherePredicate]>,
+    fields: impl IntoIterator<Item = &'a Column>,
+    field_bound: impl Fn(&Column) -> Option<TokenStream>,
+) -> syn::Generics {
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+
+    match bound {
+        Some(bound) => where_clause.predicates.extend(bound.iter().cloned()),
+        None => {
+            for field in fields {
+                if !mentions_any(field.rtype.to_token_stream(), &params) {
+                    continue;
+                }
+
+                if let Some(field_bound) = field_bound(field) {
+                    let rtype = &field.rtype;
+                    where_clause
+                        .predicates
+                        .push(parse_quote! { #rtype: #field_bound });
+                }
+            }
+        }
+    }
+
+    generics
+}
+
+/// Returns true if `ty` mentions any of the type parameters of `generics`.
+pub fn mentions_params(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    mentions_any(ty.to_token_stream(), &params)
+}
+
+fn mentions_any(tokens: TokenStream, idents: &[Ident]) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Ident(ident) => idents.contains(&ident),
+        proc_macro2::TokenTree::Group(group) => mentions_any(group.stream(), idents),
+        _ => false,
+    })
+}
+
+/// Options for the `#[df]` attribute when it is attached to a struct or enum
+/// instead of to one of its fields.
+pub struct ContainerAttr(Vec<ContainerAttrOption>);
+
+impl Parse for ContainerAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let p =
+            syn::punctuated::Punctuated::<ContainerAttrOption, Token![,]>::parse_terminated(input)?;
+        Ok(Self(p.into_iter().collect()))
+    }
+}
+
+pub enum ContainerAttrOption {
+    RenameAll(RenameRule),
+    Categorical(bool),
+    Tag(syn::LitStr),
+    Bound(Vec<syn::WherePredicate>),
+    Transparent,
+}
+
+impl Parse for ContainerAttrOption {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let id: Ident = input.parse()?;
+
+        match id.to_string().as_str() {
+            "rename_all" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let rule: syn::LitStr = input.parse()?;
+                Ok(Self::RenameAll(RenameRule::parse(&rule)?))
+            }
+            "tag" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let name = input.parse()?;
+                Ok(Self::Tag(name))
+            }
+            "bound" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let bound: syn::LitStr = input.parse()?;
+                let predicates = bound.parse_with(
+                    syn::punctuated::Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated,
+                )?;
+                Ok(Self::Bound(predicates.into_iter().collect()))
+            }
+            "transparent" => Ok(Self::Transparent),
+            "dtype" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let dtype: Ident = input.parse()?;
+                match dtype.to_string().as_str() {
+                    "Utf8" => Ok(Self::Categorical(false)),
+                    "Categorical" if !cfg!(feature = "dtype-categorical") => Err(syn::Error::new(
+                        dtype.span(),
+                        "`Categorical` can only be used with the `dtype-categorical` feature of \
+                         polars-derive",
+                    )),
+                    "Categorical" => Ok(Self::Categorical(true)),
+                    _ => Err(syn::Error::new(
+                        dtype.span(),
+                        "enums can only be stored as Utf8 or Categorical",
+                    )),
+                }
+            }
+            _ => Err(syn::Error::new(id.span(), "invalid attribute parameter")),
+        }
+    }
 }
 
 pub struct Attr(Vec<AttrOption>);
@@ -130,21 +762,68 @@ pub enum AttrOption {
     AsRef(syn::Type),
     Dtype(DataType),
     Optional(bool),
-    TryFrom { borrow: bool },
-    SerializeWith { fun: syn::ExprPath, borrow: bool },
-    DeserializeWith { fun: syn::ExprPath, borrow: bool },
+    TryFrom {
+        borrow: bool,
+    },
+    TryInto(syn::Type),
+    SerializeWith {
+        fun: syn::ExprPath,
+        borrow: bool,
+        fallible: bool,
+    },
+    DeserializeWith {
+        fun: syn::ExprPath,
+        borrow: bool,
+    },
+    Flatten,
+    Prefix(syn::LitStr),
+    Rename(syn::LitStr),
+    Alias(syn::LitStr),
+    Skip,
+    SkipSerializing,
+    SkipDeserializing,
+    Default(DefaultValue),
+    TimeZone(syn::LitStr),
+    TimeUnit((TimeUnit, proc_macro2::Span)),
 }
 
+#[derive(Clone)]
 pub enum ConvertInto {
     Into(syn::Type),
+    TryInto(syn::Type),
     AsRef(syn::Type),
-    /// a custom user-supplied conversion function
+    /// a custom user-supplied conversion function, which returns a `Result` if
+    /// it is fallible
     Custom {
         fun: syn::ExprPath,
         borrow: bool,
+        fallible: bool,
     },
 }
 
+impl ConvertInto {
+    /// Returns true if this conversion can fail.
+    pub fn is_fallible(&self) -> bool {
+        matches!(self, Self::TryInto(_) | Self::Custom { fallible: true, .. })
+    }
+
+    /// Returns a string which identifies this conversion, so that the
+    /// conversions of two fields can be compared.
+    fn key(&self) -> String {
+        match self {
+            Self::Into(ty) => format!("into {}", ty.to_token_stream()),
+            Self::TryInto(ty) => format!("try_into {}", ty.to_token_stream()),
+            Self::AsRef(ty) => format!("as_ref {}", ty.to_token_stream()),
+            Self::Custom {
+                fun,
+                borrow,
+                fallible,
+            } => format!("{} {borrow} {fallible}", fun.to_token_stream()),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub enum ConvertFrom {
     TryFrom {
         borrow: bool,
@@ -156,6 +835,16 @@ pub enum ConvertFrom {
     },
 }
 
+impl ConvertFrom {
+    /// Same as [`ConvertInto::key`].
+    fn key(&self) -> String {
+        match self {
+            Self::TryFrom { borrow } => format!("try_from {borrow}"),
+            Self::Custom { fun, borrow } => format!("{} {borrow}", fun.to_token_stream()),
+        }
+    }
+}
+
 impl Parse for AttrOption {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let id: Ident = input.parse()?;
@@ -166,6 +855,11 @@ impl Parse for AttrOption {
                 let ty: syn::Type = input.parse()?;
                 Ok(Self::Into(ty))
             }
+            "try_into" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let ty: syn::Type = input.parse()?;
+                Ok(Self::TryInto(ty))
+            }
             "as_ref" => {
                 let _ = input.parse::<Token![=]>()?; // skip '='
                 let ty: syn::Type = input.parse()?;
@@ -184,7 +878,11 @@ impl Parse for AttrOption {
             "serialize_with" => {
                 let _ = input.parse::<Token![=]>()?; // skip '='
                 let fun = input.parse()?;
-                Ok(Self::SerializeWith { fun, borrow: false })
+                Ok(Self::SerializeWith {
+                    fun,
+                    borrow: false,
+                    fallible: false,
+                })
             }
             "deserialize_with" => {
                 let _ = input.parse::<Token![=]>()?; // skip '='
@@ -194,13 +892,84 @@ impl Parse for AttrOption {
             "serialize_with_borrow" => {
                 let _ = input.parse::<Token![=]>()?; // skip '='
                 let fun = input.parse()?;
-                Ok(Self::SerializeWith { fun, borrow: true })
+                Ok(Self::SerializeWith {
+                    fun,
+                    borrow: true,
+                    fallible: false,
+                })
+            }
+            "try_serialize_with" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let fun = input.parse()?;
+                Ok(Self::SerializeWith {
+                    fun,
+                    borrow: false,
+                    fallible: true,
+                })
+            }
+            "try_serialize_with_borrow" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let fun = input.parse()?;
+                Ok(Self::SerializeWith {
+                    fun,
+                    borrow: true,
+                    fallible: true,
+                })
             }
             "deserialize_with_borrow" => {
                 let _ = input.parse::<Token![=]>()?; // skip '='
                 let fun = input.parse()?;
                 Ok(Self::DeserializeWith { fun, borrow: true })
             }
+            "prefix" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let prefix = input.parse()?;
+                Ok(Self::Prefix(prefix))
+            }
+            "flatten" => Ok(Self::Flatten),
+            "skip" => Ok(Self::Skip),
+            "skip_serializing" => Ok(Self::SkipSerializing),
+            "skip_deserializing" => Ok(Self::SkipDeserializing),
+            "default" => {
+                if input.peek(Token![=]) {
+                    let _ = input.parse::<Token![=]>()?; // skip '='
+                    let fun = input.parse()?;
+                    Ok(Self::Default(DefaultValue::Path(fun)))
+                } else {
+                    Ok(Self::Default(DefaultValue::Trait))
+                }
+            }
+            "alias" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let name = input.parse()?;
+                Ok(Self::Alias(name))
+            }
+            "rename" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let name = input.parse()?;
+                Ok(Self::Rename(name))
+            }
+            "timezone" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let tz = input.parse()?;
+                Ok(Self::TimeZone(tz))
+            }
+            "time_unit" => {
+                let _ = input.parse::<Token![=]>()?; // skip '='
+                let unit: syn::LitStr = input.parse()?;
+                let tu = match unit.value().as_str() {
+                    "ms" => TimeUnit::Milliseconds,
+                    "us" => TimeUnit::Microseconds,
+                    "ns" => TimeUnit::Nanoseconds,
+                    _ => {
+                        return Err(syn::Error::new(
+                            unit.span(),
+                            "invalid time unit, expected \"ms\", \"us\" or \"ns\"",
+                        ))
+                    }
+                };
+                Ok(Self::TimeUnit((tu, unit.span())))
+            }
             "try_from" => Ok(Self::TryFrom { borrow: false }),
             "try_from_borrow" => Ok(Self::TryFrom { borrow: true }),
             _ => Err(syn::Error::new(id.span(), "invalid attribute parameter")),
@@ -208,106 +977,257 @@ impl Parse for AttrOption {
     }
 }
 
-/// Gets the corresponding Polars [`DataType`] for a given Rust type.
-fn dtype_for_rtype(ty: &syn::Type) -> syn::Result<DataType> {
+/// Returns the last segment of `path` if the path may name one of the types
+/// which are recognized by [`dtype_for_rtype`]: either it is a single
+/// identifier, which is assumed to be imported, or it goes through one of the
+/// modules that these types come from. Other paths, like `crate::Duration`,
+/// always name types of the user. Paths through `time` are only taken for the
+/// `time` crate as described in [`in_time_crate`].
+fn known_segment(path: &syn::Path) -> Option<&syn::PathSegment> {
+    const MODULES: &[&[&str]] = &[
+        &["std", "string"],
+        &["std", "vec"],
+        &["alloc", "string"],
+        &["alloc", "vec"],
+        &["std", "option"],
+        &["core", "option"],
+        &["chrono"],
+        &["chrono", "naive"],
+        &["polars", "export", "chrono"],
+        &["polars", "export", "chrono", "naive"],
+        &["std", "time"],
+        &["core", "time"],
+        &["time"],
+    ];
+
+    let segments: Vec<_> = path.segments.iter().collect();
+    let (last, module) = segments.split_last()?;
+
+    if module.is_empty() {
+        return if path.leading_colon.is_none() {
+            Some(last)
+        } else {
+            None
+        };
+    }
+
+    let known = MODULES.iter().any(|known| {
+        known.len() == module.len()
+            && known
+                .iter()
+                .zip(module)
+                .all(|(known, segment)| segment.ident == known && segment.arguments.is_empty())
+    });
+
+    if known && (!in_module(path, &["time"]) || in_time_crate(path)) {
+        Some(last)
+    } else {
+        None
+    }
+}
+
+/// Returns the arguments of the type at `path` if it is the generic type
+/// `name` (ex.: `Vec`), named in the same way as in [`known_segment`], like
+/// `Vec<T>` or `std::vec::Vec<T>`.
+fn known_generic<'a>(path: &'a syn::Path, name: &str) -> Option<&'a syn::PathArguments> {
+    let segment = known_segment(path)?;
+
+    if segment.ident == name {
+        Some(&segment.arguments)
+    } else {
+        None
+    }
+}
+
+/// Returns the feature which is needed to store the type at `path`, if it is
+/// one of the types of `std::time` or of the `time` crate, named through its
+/// module, and the feature is not enabled. Otherwise, the type would be taken
+/// for the chrono type of the same name (ex.: `Duration`). Without the `time`
+/// feature, only `::time::...` is taken for the `time` crate.
+fn missing_feature(path: &syn::Path) -> Option<&'static str> {
+    let modules: Vec<_> = path.segments.iter().map(|segment| &segment.ident).collect();
+    let (last, module) = modules.split_last()?;
+
+    if (module == ["std", "time"] || module == ["core", "time"])
+        && (*last == "SystemTime" || *last == "Duration")
+        && !cfg!(feature = "std-time")
+    {
+        return Some("std-time");
+    }
+
+    if module == ["time"]
+        && path.leading_colon.is_some()
+        && (*last == "OffsetDateTime" || *last == "Date" || *last == "Duration")
+        && !cfg!(feature = "time")
+    {
+        return Some("time");
+    }
+
+    None
+}
+
+/// Whether `path` names an item of `module`, like `std::time::SystemTime`.
+fn in_module(path: &syn::Path, module: &[&str]) -> bool {
+    let segments: Vec<_> = path.segments.iter().collect();
+
+    segments.len() == module.len() + 1
+        && segments
+            .iter()
+            .zip(module)
+            .all(|(segment, name)| segment.ident == name)
+}
+
+/// Whether `path` names an item of the `time` crate, like `time::Date`. The
+/// user might have a module called `time` of their own, so a path like this
+/// is only taken for the crate if it starts with `::time`, or if the `time`
+/// feature is enabled, in which case other modules called `time` have to be
+/// named like `self::time`.
+fn in_time_crate(path: &syn::Path) -> bool {
+    in_module(path, &["time"]) && (path.leading_colon.is_some() || cfg!(feature = "time"))
+}
+
+/// Gets the corresponding Polars [`DataType`] for a given Rust type. Returns
+/// `None` if the type is not known, in which case it is resolved through the
+/// `IntoColumn` and `FromColumn` traits instead.
+fn dtype_for_rtype(ty: &syn::Type) -> syn::Result<Option<DataType>> {
     match ty {
-        syn::Type::Path(ty) => {
-            if let Some(id) = ty.path.get_ident() {
+        syn::Type::Path(ty) if ty.qself.is_none() => {
+            if let Some(feature) = missing_feature(&ty.path) {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format!(
+                        "`{}` can only be stored with the `{feature}` feature of polars-derive",
+                        ty.to_token_stream().to_string().replace(' ', "")
+                    ),
+                ));
+            }
+
+            let segment = match known_segment(&ty.path) {
+                Some(segment) => segment,
+                None => return Ok(None),
+            };
+
+            if segment.arguments.is_empty() {
                 // type with no type params
-                match id.to_string().as_str() {
-                    "u8" => return Ok(DataType::UInt8),
-                    "u16" => return Ok(DataType::UInt16),
-                    "u32" => return Ok(DataType::UInt32),
-                    "u64" => return Ok(DataType::UInt64),
-                    "i8" => return Ok(DataType::Int8),
-                    "i16" => return Ok(DataType::Int16),
-                    "i32" => return Ok(DataType::Int32),
-                    "i64" => return Ok(DataType::Int64),
-                    "bool" => return Ok(DataType::Boolean),
-                    "f32" => return Ok(DataType::Float32),
-                    "f64" => return Ok(DataType::Float64),
-                    "String" | "str" => return Ok(DataType::Utf8),
-                    "NaiveDateTime" => return Ok(DataType::Datetime(TimeUnit::Milliseconds, None)),
-                    _ => {}
-                }
-            } else if ty.path.leading_colon.is_none() && ty.path.segments.len() == 1 {
-                // type with some type params
-                let id = &ty.path.segments[0].ident;
+                let dtype = match segment.ident.to_string().as_str() {
+                    "u8" => DataType::UInt8,
+                    "u16" => DataType::UInt16,
+                    "u32" => DataType::UInt32,
+                    "u64" => DataType::UInt64,
+                    "i8" => DataType::Int8,
+                    "i16" => DataType::Int16,
+                    "i32" => DataType::Int32,
+                    "i64" => DataType::Int64,
+                    "bool" => DataType::Boolean,
+                    "f32" => DataType::Float32,
+                    "f64" => DataType::Float64,
+                    "String" | "str" => DataType::Utf8,
+                    "NaiveDateTime" => DataType::Datetime(TimeUnit::Milliseconds, None),
+                    "NaiveDate" => DataType::Date,
+                    "NaiveTime" => DataType::Time,
+                    "Duration" | "TimeDelta" => DataType::Duration(TimeUnit::Milliseconds),
+                    // these names are too common to be recognized on their
+                    // own, so they have to be named through their module
+                    "SystemTime" if in_module(&ty.path, &["std", "time"]) => {
+                        DataType::Datetime(TimeUnit::Milliseconds, None)
+                    }
+                    "OffsetDateTime" if in_time_crate(&ty.path) => {
+                        // the time zone can be set using
+                        // #[df(timezone = "...")]
+                        DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".to_owned()))
+                    }
+                    "Date" if in_time_crate(&ty.path) => DataType::Date,
+                    _ => return Ok(None),
+                };
 
-                match id.to_string().as_str() {
-                    "Vec" => {
-                        let args = &ty.path.segments[0].arguments;
-
-                        if let syn::PathArguments::AngleBracketed(args) = args {
-                            if args.args.len() == 1 {
-                                if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
-                                    // if it's a Vec<u8>, infer Binary; otherwise, infer List(T)
-                                    if let syn::Type::Path(ty) = ty {
-                                        if ty.path.is_ident("u8") {
-                                            return Ok(DataType::Binary);
-                                        }
-                                    }
+                return Ok(Some(dtype));
+            }
+
+            // type with some type params
+            match segment.ident.to_string().as_str() {
+                "Vec" => {
+                    let args = &segment.arguments;
 
-                                    return Ok(DataType::List(Box::new(dtype_for_rtype(ty)?)));
+                    if let syn::PathArguments::AngleBracketed(args) = args {
+                        if args.args.len() == 1 {
+                            if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                                // if it's a Vec<u8>, infer Binary; otherwise, infer List(T)
+                                if let syn::Type::Path(ty) = ty {
+                                    if ty.path.is_ident("u8") {
+                                        return Ok(Some(DataType::Binary));
+                                    }
                                 }
-                            } else {
-                                return Err(syn::Error::new_spanned(
-                                    args,
-                                    "invalid arguments for Vec",
-                                ));
+
+                                // the items of a list can be null
+                                let (ty, _) = strip_option(ty)?;
+                                return Ok(dtype_for_rtype(ty)?
+                                    .map(|dtype| DataType::List(Box::new(dtype))));
                             }
-                        } else {
-                            return Err(syn::Error::new_spanned(args, "invalid arguments for Vec"));
                         }
                     }
 
-                    // to support chrono
-                    "DateTime" => {
-                        let args = &ty.path.segments[0].arguments;
-
-                        if let syn::PathArguments::AngleBracketed(args) = args {
-                            if args.args.len() == 1 {
-                                if let Some(syn::GenericArgument::Type(syn::Type::Path(_))) =
-                                    args.args.first()
-                                {
-                                    return Ok(DataType::Datetime(
-                                        TimeUnit::Milliseconds,
-                                        // actual time zone will be filled in later
-                                        Some("PLACEHOLDER".to_owned()),
-                                    ));
-                                }
+                    Err(syn::Error::new_spanned(args, "invalid arguments for Vec"))
+                }
+
+                // to support chrono
+                "DateTime" => {
+                    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                        if args.args.len() == 1 {
+                            if let Some(syn::GenericArgument::Type(syn::Type::Path(_))) =
+                                args.args.first()
+                            {
+                                // the time zone can be set using
+                                // #[df(timezone = "...")]
+                                return Ok(Some(DataType::Datetime(
+                                    TimeUnit::Milliseconds,
+                                    Some("UTC".to_owned()),
+                                )));
                             }
                         }
                     }
 
-                    _ => {}
+                    Ok(None)
                 }
+
+                _ => Ok(None),
             }
         }
+        #[cfg(not(feature = "dtype-struct"))]
+        syn::Type::Tuple(ty) => Err(syn::Error::new_spanned(
+            ty,
+            "tuples can only be used with the `dtype-struct` feature of polars-derive",
+        )),
+        #[cfg(feature = "dtype-struct")]
         syn::Type::Tuple(ty) => {
             // map tuples to a struct where fields are named 0, 1, and so on
-            let fields: Vec<Field> = ty
-                .elems
-                .iter()
-                .enumerate()
-                .map(|(idx, elem)| -> syn::Result<Field> {
-                    Ok(Field::new(idx.to_string().as_str(), dtype_for_rtype(elem)?))
-                })
-                .collect::<syn::Result<Vec<Field>>>()?;
+            let mut fields = vec![];
+            for (idx, elem) in ty.elems.iter().enumerate() {
+                match dtype_for_rtype(elem)? {
+                    Some(dtype) => fields.push(Field::new(idx.to_string().as_str(), dtype)),
+                    None => return Ok(None),
+                }
+            }
 
-            return Ok(DataType::Struct(fields));
+            Ok(Some(DataType::Struct(fields)))
         }
-        syn::Type::Array(ty) => return Ok(DataType::List(Box::new(dtype_for_rtype(&*ty.elem)?))),
-        syn::Type::Slice(ty) => return Ok(DataType::List(Box::new(dtype_for_rtype(&*ty.elem)?))),
-        syn::Type::Reference(ty) => return dtype_for_rtype(&*ty.elem),
-        syn::Type::Paren(ty) => return dtype_for_rtype(&*ty.elem),
-        _ => {}
-    };
+        syn::Type::Array(syn::TypeArray { elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { elem, .. }) => {
+            let (elem, _) = strip_option(elem)?;
+            Ok(dtype_for_rtype(elem)?.map(|dtype| DataType::List(Box::new(dtype))))
+        }
+        syn::Type::Reference(ty) => {
+            // like Vec<u8>, &[u8] is stored as Binary instead of a list
+            if let syn::Type::Slice(elem) = &*ty.elem {
+                if matches!(&*elem.elem, syn::Type::Path(elem) if elem.path.is_ident("u8")) {
+                    return Ok(Some(DataType::Binary));
+                }
+            }
 
-    return Err(syn::Error::new_spanned(
-        ty,
-        "unknown type, please specify dtype explicitly",
-    ));
+            dtype_for_rtype(&ty.elem)
+        }
+        syn::Type::Paren(ty) => dtype_for_rtype(&ty.elem),
+        _ => Ok(None),
+    }
 }
 
 pub(crate) fn rtype_for_dtype(ty: &DataType) -> syn::Type {
@@ -330,7 +1250,7 @@ pub(crate) fn rtype_for_dtype(ty: &DataType) -> syn::Type {
         DataType::Time => parse_quote! { ::polars::export::chrono::NaiveTime },
         DataType::Binary => parse_quote! { Vec<u8> },
         DataType::List(inner) => {
-            let inner = rtype_for_dtype(&*inner);
+            let inner = rtype_for_dtype(inner);
             parse_quote! { Vec<#inner> }
         }
         _ => parse_quote! { _ },
@@ -338,33 +1258,169 @@ pub(crate) fn rtype_for_dtype(ty: &DataType) -> syn::Type {
 }
 
 /// Gets the corresponding Polars [`DataType`] for a given Rust type. Allows
-/// `Option`, returns a tuple with a `DataType` and a bool indicating whether
-/// the Rust type was optional or not.
-fn dtype_for_rtype_opt(ty: &syn::Type) -> syn::Result<(DataType, bool)> {
-    if let syn::Type::Path(ty) = ty {
-        if ty.path.leading_colon.is_none() && ty.path.segments.len() == 1 {
-            // type with some type params
-            let id = &ty.path.segments[0].ident;
-            if id == "Option" {
-                let args = &ty.path.segments[0].arguments;
-
-                if let syn::PathArguments::AngleBracketed(args) = args {
-                    if args.args.len() == 1 {
-                        if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
-                            return Ok((dtype_for_rtype(ty)?, true));
-                        }
-                    }
+/// `Option`, returns a tuple with a `DataType`, the Rust type without the
+/// `Option` and a bool indicating whether the Rust type was optional or not.
+///
+/// Types which are not recognized are resolved through the `IntoColumn` and
+/// `FromColumn` traits instead, in which case no `DataType` is returned.
+fn dtype_for_rtype_opt(ty: &syn::Type) -> syn::Result<(Option<DataType>, &syn::Type, bool)> {
+    let (ty, optional) = strip_option(ty)?;
+    Ok((dtype_for_rtype(ty)?, ty, optional))
+}
+
+/// Returns the `Datetime` data type in `dtype`, which is either `dtype` itself
+/// or the type of the items of a list.
+fn datetime_dtype(dtype: &mut DataType) -> Option<&mut DataType> {
+    match dtype {
+        DataType::Datetime(_, _) => Some(dtype),
+        DataType::List(inner) => datetime_dtype(inner),
+        _ => None,
+    }
+}
+
+/// Checks that the time zone `tz` can be set on a field of type `ty`, which
+/// has to hold values that are in a time zone, unlike `NaiveDateTime`. The
+/// values of `DateTime<FixedOffset>`, `DateTime<Utc>` and `OffsetDateTime` are
+/// read back in this time zone, so it has to be `UTC` or an offset.
+fn check_timezone(tz: &syn::LitStr, ty: &syn::Type) -> syn::Result<()> {
+    #[cfg(feature = "time")]
+    let offset_datetime = is_offset_datetime(ty);
+    #[cfg(not(feature = "time"))]
+    let offset_datetime = false;
+
+    let zone = datetime_zone(ty);
+    if zone.is_none() && !offset_datetime {
+        return Err(syn::Error::new(
+            tz.span(),
+            "timezone can only be used on fields which are in a time zone, like DateTime<Tz>, \
+             not on naive datetimes",
+        ));
+    }
+
+    let offset_only = offset_datetime
+        || matches!(zone, Some(syn::Type::Path(zone))
+        if zone.path.segments.last().is_some_and(|segment| {
+            segment.ident == "FixedOffset" || segment.ident == "Utc"
+        }));
+
+    let value = tz.value();
+    if offset_only && value != "UTC" && FixedOffset::from_str(&value).is_err() {
+        return Err(syn::Error::new(
+            tz.span(),
+            "the time zone of this field has to be \"UTC\" or an offset like \"+02:00\"",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the time unit of a `Datetime` or `Duration` data type, possibly
+/// inside of a list.
+fn dtype_time_unit(dtype: &mut DataType) -> Option<&mut TimeUnit> {
+    match dtype {
+        DataType::Datetime(tu, _) | DataType::Duration(tu) => Some(tu),
+        DataType::List(inner) => dtype_time_unit(inner),
+        _ => None,
+    }
+}
+
+/// Whether the given type, possibly inside of a list, is the `time` crate's
+/// `OffsetDateTime`, which is read in the time zone of the column like a
+/// `DateTime<FixedOffset>`.
+#[cfg(feature = "time")]
+pub(crate) fn is_offset_datetime(ty: &syn::Type) -> bool {
+    if let Some((item, _)) = list_item_type(ty) {
+        return is_offset_datetime(item);
+    }
+
+    match ty {
+        syn::Type::Path(ty) => {
+            in_time_crate(&ty.path)
+                && ty
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "OffsetDateTime")
+        }
+        _ => false,
+    }
+}
+
+/// If the given type is a `DateTime<Tz>`, possibly inside of a list, returns
+/// `Tz`. Otherwise, the type is assumed to be a `NaiveDateTime`.
+pub(crate) fn datetime_zone(ty: &syn::Type) -> Option<&syn::Type> {
+    if let Some((item, _)) = list_item_type(ty) {
+        return datetime_zone(item);
+    }
+
+    let segment = match ty {
+        syn::Type::Path(ty) => ty.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "DateTime" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(tz)) => Some(tz),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// If the given type is a list (`Vec<T>`, `[T; N]` or `[T]`, possibly behind a
+/// reference), returns the type of its items with the `Option` stripped off,
+/// and whether the items are optional.
+pub(crate) fn list_item_type(ty: &syn::Type) -> Option<(&syn::Type, bool)> {
+    let item = match ty {
+        syn::Type::Path(ty) if ty.qself.is_none() => match known_generic(&ty.path, "Vec")? {
+            syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                match args.args.first() {
+                    Some(syn::GenericArgument::Type(ty)) => ty,
+                    _ => return None,
                 }
+            }
+            _ => return None,
+        },
+        syn::Type::Array(ty) => &ty.elem,
+        syn::Type::Slice(ty) => &ty.elem,
+        syn::Type::Reference(ty) => return list_item_type(&ty.elem),
+        syn::Type::Paren(ty) => return list_item_type(&ty.elem),
+        _ => return None,
+    };
 
-                return Err(syn::Error::new_spanned(
-                    args,
-                    "invalid arguments for Option",
-                ));
+    strip_option(item).ok()
+}
+
+/// If the given type is an `Option<T>`, returns `T` and `true`. Otherwise,
+/// returns the type unchanged and `false`.
+fn strip_option(ty: &syn::Type) -> syn::Result<(&syn::Type, bool)> {
+    if let syn::Type::Path(path) = ty {
+        if path.qself.is_some() {
+            return Ok((ty, false));
+        }
+
+        if let Some(args) = known_generic(&path.path, "Option") {
+            if let syn::PathArguments::AngleBracketed(args) = args {
+                if args.args.len() == 1 {
+                    if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                        return Ok((ty, true));
+                    }
+                }
             }
+
+            return Err(syn::Error::new_spanned(
+                args,
+                "invalid arguments for Option",
+            ));
         }
     };
 
-    Ok((dtype_for_rtype(ty)?, false))
+    Ok((ty, false))
 }
 
 pub(crate) fn expr_to_dtype(ex: &syn::Expr) -> syn::Result<DataType> {
@@ -373,7 +1429,7 @@ pub(crate) fn expr_to_dtype(ex: &syn::Expr) -> syn::Result<DataType> {
             if let syn::Expr::Path(callee) = &**func {
                 if callee.path.is_ident("List") && args.len() == 1 {
                     return Ok(DataType::List(Box::new(expr_to_dtype(
-                        &*args.first().unwrap(),
+                        args.first().unwrap(),
                     )?)));
                 }
 
@@ -434,8 +1490,12 @@ pub(crate) fn expr_to_dtype(ex: &syn::Expr) -> syn::Result<DataType> {
                     "Utf8" => return Ok(DataType::Utf8),
                     "Date" => return Ok(DataType::Date),
                     "Time" => return Ok(DataType::Time),
-                    "Null" => return Ok(DataType::Null),
-                    "Unknown" => return Ok(DataType::Unknown),
+                    "Null" | "Unknown" => {
+                        return Err(syn::Error::new_spanned(
+                            ex,
+                            format!("{id} cannot be used as the dtype of a field"),
+                        ))
+                    }
                     #[cfg(feature = "dtype-binary")]
                     "Binary" => return Ok(DataType::Binary),
                     _ => {}
@@ -446,7 +1506,18 @@ pub(crate) fn expr_to_dtype(ex: &syn::Expr) -> syn::Result<DataType> {
         _ => {}
     }
 
-    return Err(syn::Error::new_spanned(ex, "invalid dtype"));
+    Err(syn::Error::new_spanned(ex, "invalid dtype"))
+}
+
+/// Returns a compile error for a data type which the derive macros can't
+/// read or write. These are rejected when the attributes are parsed, so this
+/// is only a fallback.
+pub(crate) fn unsupported_dtype(dtype: &DataType) -> TokenStream {
+    syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("columns of type {dtype} are not supported"),
+    )
+    .to_compile_error()
 }
 
 pub(crate) fn dtype_to_expr(dtype: &DataType) -> TokenStream {
@@ -469,7 +1540,7 @@ pub(crate) fn dtype_to_expr(dtype: &DataType) -> TokenStream {
         DataType::Datetime(tu, tz) => {
             let tu = time_unit_to_expr(*tu);
             let tz = match tz {
-                Some(tz) => quote! { Some(#tz) },
+                Some(tz) => quote! { Some(::std::string::String::from(#tz)) },
                 None => quote! { None },
             };
             quote! { ::polars::datatypes::DataType::Datetime(#tu, #tz) }
@@ -480,16 +1551,27 @@ pub(crate) fn dtype_to_expr(dtype: &DataType) -> TokenStream {
         }
         DataType::Time => quote! { ::polars::datatypes::DataType::Time },
         DataType::List(inner) => {
-            let inner = dtype_to_expr(&*inner);
+            let inner = dtype_to_expr(inner);
             quote! { ::polars::datatypes::DataType::List(::std::boxed::Box::new(#inner)) }
         }
+        #[cfg(feature = "dtype-struct")]
+        DataType::Struct(fields) => {
+            let fields = fields.iter().map(|field| {
+                let name = field.name().as_str();
+                let dtype = dtype_to_expr(field.data_type());
+                quote! { ::polars::datatypes::Field::new(#name, #dtype) }
+            });
+            quote! { ::polars::datatypes::DataType::Struct(vec![#(#fields),*]) }
+        }
         DataType::Null => quote! { ::polars::datatypes::DataType::Null },
         DataType::Unknown => quote! { ::polars::datatypes::DataType::Unknown },
-        other => unimplemented!("unimplemented dtype {other:?}"),
+        // some data types only exist when certain Polars features are enabled
+        #[allow(unreachable_patterns)]
+        other => unsupported_dtype(other),
     }
 }
 
-fn time_unit_to_expr(tu: TimeUnit) -> TokenStream {
+pub(crate) fn time_unit_to_expr(tu: TimeUnit) -> TokenStream {
     use quote::quote;
     match tu {
         TimeUnit::Nanoseconds => quote! { ::polars::datatypes::TimeUnit::Nanoseconds },
@@ -497,3 +1579,139 @@ fn time_unit_to_expr(tu: TimeUnit) -> TokenStream {
         TimeUnit::Milliseconds => quote! { ::polars::datatypes::TimeUnit::Milliseconds },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the error message for deriving on `input`, which should fail.
+    fn error(input: TokenStream) -> String {
+        match syn::parse2::<Input>(input) {
+            Ok(_) => panic!("the input should be rejected"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "dtype-categorical"))]
+    fn test_categorical_without_feature() {
+        let err = error(quote! {
+            #[df(dtype = Categorical)]
+            enum Status {
+                Active,
+                Inactive,
+            }
+        });
+        assert!(err.contains("`dtype-categorical` feature"), "{err}");
+    }
+
+    #[test]
+    #[cfg(not(feature = "time"))]
+    fn test_time_crate_without_feature() {
+        let err = error(quote! {
+            struct Booking {
+                day: ::time::Date,
+            }
+        });
+        assert!(err.contains("`time` feature"), "{err}");
+    }
+
+    #[test]
+    fn test_rename_all_on_tuple_struct() {
+        let err = error(quote! {
+            #[df(rename_all = "UPPERCASE")]
+            struct Point(f64, f64);
+        });
+        assert!(err.contains("tuple structs"), "{err}");
+    }
+
+    #[test]
+    fn test_timezone_on_naive_datetime() {
+        let err = error(quote! {
+            struct Event {
+                #[df(timezone = "Europe/Berlin")]
+                at: NaiveDateTime,
+            }
+        });
+        assert!(err.contains("not on naive datetimes"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_offset() {
+        for ty in [
+            quote! { DateTime<FixedOffset> },
+            quote! { Option<DateTime<Utc>> },
+        ] {
+            let err = error(quote! {
+                struct Event {
+                    #[df(timezone = "Europe/Berlin")]
+                    at: #ty,
+                }
+            });
+            assert!(err.contains("an offset like"), "{err}");
+        }
+
+        let input = quote! {
+            struct Event {
+                #[df(timezone = "+02:00")]
+                at: DateTime<FixedOffset>,
+                #[df(timezone = "UTC")]
+                created: DateTime<Utc>,
+                #[df(timezone = "Europe/Berlin")]
+                local: DateTime<Tz>,
+            }
+        };
+        assert!(syn::parse2::<Input>(input).is_ok());
+    }
+
+    #[test]
+    fn test_shared_column_conversion() {
+        let err = error(quote! {
+            enum Shape {
+                Circle {
+                    #[df(dtype = Utf8, try_from)]
+                    id: Id,
+                },
+                Square {
+                    #[df(dtype = Utf8, deserialize_with = parse_id)]
+                    id: Id,
+                },
+            }
+        });
+        assert!(err.contains("field id has a different type"), "{err}");
+    }
+
+    #[test]
+    fn test_shared_column_aliases() {
+        let err = error(quote! {
+            enum Shape {
+                Circle {
+                    #[df(alias = "radius")]
+                    size: f64,
+                },
+                Square {
+                    #[df(alias = "side")]
+                    size: f64,
+                },
+            }
+        });
+        assert!(err.contains("field size has a different type"), "{err}");
+    }
+
+    #[test]
+    fn test_shared_column_defaults() {
+        let err = error(quote! {
+            enum Shape {
+                Circle {
+                    #[df(default = unit_size)]
+                    size: f64,
+                },
+                Square {
+                    #[df(default = double_size)]
+                    size: f64,
+                },
+            }
+        });
+        assert!(err.contains("field size has a different type"), "{err}");
+    }
+}
@@ -2,91 +2,737 @@ use polars::prelude::DataType;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 
-use crate::common::{ConvertFrom, Template};
+use crate::common::{
+    bounded_generics, datetime_zone, dtype_to_expr, list_item_type, unsupported_dtype, Column,
+    ConvertFrom, EnumTemplate, Input, Template,
+};
 
 pub fn derive(input: TokenStream2) -> TokenStream2 {
-    let template: Template = match syn::parse2(input) {
-        Ok(template) => template,
-        Err(err) => return err.to_compile_error().into(),
+    match syn::parse2(input) {
+        Ok(Input::Struct(template)) if template.transparent => derive_transparent(template),
+        Ok(Input::Struct(template)) => derive_struct(template),
+        Ok(Input::Enum(template)) if template.is_tagged() => derive_tagged_enum(template),
+        Ok(Input::Enum(template)) => derive_enum(template),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+/// Derives `FromDataFrameRef`, which is implemented in the same way as
+/// `FromDataFrame` except that the `DataFrame` is borrowed for the lifetime of
+/// the rows.
+pub fn derive_ref(input: TokenStream2) -> TokenStream2 {
+    let (item, generics, bound, fields, rows) = match syn::parse2(input) {
+        Ok(Input::Struct(template)) if template.transparent => {
+            return syn::Error::new(
+                template.structure.ident.span(),
+                "transparent structs cannot derive FromDataFrameRef",
+            )
+            .to_compile_error()
+        }
+        Ok(Input::Struct(template)) => {
+            let rows = rows_impl(&template, &format_ident!("df"));
+            let structure = template.structure;
+            (
+                structure.ident,
+                structure.generics,
+                template.bound,
+                template.fields,
+                rows,
+            )
+        }
+        Ok(Input::Enum(template)) if template.is_tagged() => {
+            let rows = tagged_rows_impl(&template, &format_ident!("df"), false);
+            let enumeration = template.enumeration;
+            (
+                enumeration.ident,
+                enumeration.generics,
+                template.bound,
+                template.columns,
+                rows,
+            )
+        }
+        Ok(Input::Enum(template)) => {
+            return syn::Error::new(
+                template.enumeration.ident.span(),
+                "enums without fields cannot derive FromDataFrameRef",
+            )
+            .to_compile_error()
+        }
+        Err(err) => return err.to_compile_error(),
     };
 
-    let structure = template.structure;
-    let name = structure.ident.clone();
+    let generics = bounded_generics(
+        &generics,
+        bound.as_deref(),
+        fields.iter().filter(|field| !field.skip_from),
+        field_bound,
+    );
 
-    let out = format_ident!("out");
-    let df = format_ident!("df");
+    // the rows borrow from the DataFrame for the lifetime of the structure. if
+    // it doesn't have one, the rows don't borrow anything, so any lifetime
+    // will do
+    let mut lifetimes = generics.lifetimes();
+    let (impl_lifetime, lifetime) = match (lifetimes.next(), lifetimes.next()) {
+        (None, _) => {
+            let lifetime: syn::Lifetime = syn::parse_quote! { 'df };
+            (Some(lifetime.clone()), lifetime)
+        }
+        (Some(def), None) => (None, def.lifetime.clone()),
+        (Some(_), Some(def)) => {
+            return syn::Error::new(
+                def.lifetime.span(),
+                "structures that derive FromDataFrameRef can have at most one lifetime",
+            )
+            .to_compile_error()
+        }
+    };
 
-    let field_iter_ids = template.fields.iter().map(|field| {
-        let col_name = &field.name;
-        format_ident!("c_{}", col_name)
-    });
+    let mut ref_generics = generics.clone();
+    if let Some(lifetime) = impl_lifetime {
+        ref_generics.params.insert(
+            0,
+            syn::GenericParam::Lifetime(syn::LifetimeDef::new(lifetime)),
+        );
+    }
+    let (impl_generics, _, _) = ref_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
 
-    let field_iter_inits = template.fields.iter().map(|field| {
-        let col_name = &field.name;
-        let var_name = format_ident!("c_{}", col_name);
+    let trait_ = quote! { ::polars_derive::FromDataFrameRef<#lifetime> };
 
-        let col_expr = quote! { #df.column(#col_name)? };
-        let col_expr = series_to_rtype(col_expr, &field.dtype);
+    quote! {
+        impl #impl_generics #trait_ for #item #ty_generics #where_clause {
+          fn iter_df_ref(
+            df: &#lifetime polars::frame::DataFrame,
+          ) -> Result<
+            impl Iterator<Item = Result<Self, ::polars_derive::Error>>,
+            ::polars_derive::Error,
+          > {
+            Ok(#rows)
+          }
+        }
+    }
+}
 
-        quote_spanned! {field.span=> let mut #var_name = #col_expr.into_iter(); }
-    });
+/// Returns the bound that the type of this field needs in order to be read.
+fn field_bound(field: &Column) -> Option<TokenStream2> {
+    // structures only implement FromColumn with the dtype-struct feature
+    if field.flatten.is_some() && cfg!(feature = "dtype-struct") {
+        Some(quote! { ::polars_derive::FromDataFrame + ::polars_derive::FromColumn })
+    } else if field.flatten.is_some() {
+        Some(quote! { ::polars_derive::FromDataFrame })
+    } else if field.dtype.is_none() {
+        Some(quote! { ::polars_derive::FromColumn })
+    } else {
+        None
+    }
+}
 
-    let field_iter_pats = template.fields.iter().map(|field| {
-        let col_name = &field.name;
-        let var_name = format_ident!("i_{}", col_name);
+/// Enums whose variants have no fields are read from a single column, so they
+/// only implement FromColumn. Both Utf8 and Categorical columns are accepted.
+fn derive_enum(template: EnumTemplate) -> TokenStream2 {
+    let name = template.enumeration.ident.clone();
+    let generics = bounded_generics(
+        &template.enumeration.generics,
+        template.bound.as_deref(),
+        [],
+        field_bound,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-        quote_spanned! {field.span=> Some(#var_name) }
+    let arms = template.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let name = &variant.name;
+        quote! { #name => Ok(Self::#ident) }
     });
 
-    let field_iter_getters = template.fields.iter().map(|field| {
-        let col_name = &field.name;
-        let value_name = format_ident!("{}", col_name);
-        let pat_name = format_ident!("i_{}", col_name);
+    quote! {
+        impl #impl_generics ::polars_derive::FromColumn for #name #ty_generics #where_clause {
+            fn from_column(
+                series: &::polars::series::Series,
+            ) -> Result<
+                Vec<Result<Option<Self>, ::polars_derive::Error>>,
+                ::polars_derive::Error,
+            > {
+                let values = series
+                    .cast(&::polars::datatypes::DataType::Utf8)
+                    .map_err(|_| ::polars_derive::__private::dtype_mismatch(
+                        series.name(),
+                        ::polars::datatypes::DataType::Utf8,
+                        series,
+                    ))?;
+
+                Ok(values
+                    .utf8()
+                    .expect("the column should have been cast to Utf8")
+                    .into_iter()
+                    .enumerate()
+                    .map(|(row, value)| {
+                        value
+                            .map(|value| match value {
+                                #(#arms,)*
+                                other => Err(::polars_derive::Error::UnknownVariant {
+                                    column: series.name().to_owned(),
+                                    row,
+                                    variant: other.to_owned(),
+                                }),
+                            })
+                            .transpose()
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Transparent structs are read in the same way as their only field, so they
+/// only implement FromColumn.
+fn derive_transparent(template: Template) -> TokenStream2 {
+    let name = template.structure.ident.clone();
+    let field = &template.fields[0];
+    let generics = bounded_generics(
+        &template.structure.generics,
+        template.bound.as_deref(),
+        [field],
+        |_| Some(quote! { ::polars_derive::FromColumn }),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let ty = &field.ty;
+    let member = &field.member;
+
+    quote! {
+        impl #impl_generics ::polars_derive::FromColumn for #name #ty_generics #where_clause {
+            fn from_column(
+                series: &::polars::series::Series,
+            ) -> Result<
+                Vec<Result<Option<Self>, ::polars_derive::Error>>,
+                ::polars_derive::Error,
+            > {
+                Ok(<#ty as ::polars_derive::FromColumn>::from_column(series)?
+                    .into_iter()
+                    .map(|value| value.map(|value| value.map(|value| Self { #member: value })))
+                    .collect())
+            }
+
+            fn from_required_column(
+                series: &::polars::series::Series,
+            ) -> Result<
+                Vec<Result<Option<Self>, ::polars_derive::Error>>,
+                ::polars_derive::Error,
+            > {
+                Ok(<#ty as ::polars_derive::FromColumn>::from_required_column(series)?
+                    .into_iter()
+                    .map(|value| value.map(|value| value.map(|value| Self { #member: value })))
+                    .collect())
+            }
+        }
+    }
+}
+
+fn derive_struct(template: Template) -> TokenStream2 {
+    let name = template.structure.ident.clone();
+    let generics = bounded_generics(
+        &template.structure.generics,
+        template.bound.as_deref(),
+        template.fields.iter().filter(|field| !field.skip_from),
+        field_bound,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let df = format_ident!("df");
+    let df_rows = rows_impl(&template, &df);
+    let column_rows = rows_impl(&template, &df);
+    let column_body = column_impl(&df, quote! { #column_rows.map(|row| row.map(Some)) });
+
+    // structures are read from Struct columns, which need the dtype-struct
+    // feature
+    let column_impl = if cfg!(feature = "dtype-struct") {
+        quote! {
+            impl #impl_generics ::polars_derive::FromColumn for #name #ty_generics #where_clause {
+              fn from_column(
+                series: &::polars::series::Series,
+              ) -> Result<
+                Vec<Result<Option<Self>, ::polars_derive::Error>>,
+                ::polars_derive::Error,
+              > {
+                let rows = <Self as ::polars_derive::FromColumn>::from_required_column(series)?;
+                Ok(::polars_derive::__private::struct_nulls(series, rows))
+              }
+
+              fn from_required_column(
+                series: &::polars::series::Series,
+              ) -> Result<
+                Vec<Result<Option<Self>, ::polars_derive::Error>>,
+                ::polars_derive::Error,
+              > {
+                #column_body
+              }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        impl #impl_generics ::polars_derive::FromDataFrame for #name #ty_generics #where_clause {
+          fn iter_df(
+            #df: &polars::frame::DataFrame,
+          ) -> Result<
+            impl Iterator<Item = Result<Self, ::polars_derive::Error>>,
+            ::polars_derive::Error,
+          > {
+            Ok(#df_rows)
+          }
+
+          fn from_df(
+            #df: &polars::frame::DataFrame,
+          ) -> polars::prelude::PolarsResult<Vec<Self>> {
+            Ok(<Self as ::polars_derive::FromDataFrame>::iter_df(#df)?
+              .collect::<Result<_, ::polars_derive::Error>>()?)
+          }
+        }
+
+        #column_impl
+    }
+}
+
+/// Enums whose variants have fields are read from a tag column containing the
+/// names of the variants, followed by the columns of all of the variants.
+fn derive_tagged_enum(template: EnumTemplate) -> TokenStream2 {
+    let name = template.enumeration.ident.clone();
+    let generics = bounded_generics(
+        &template.enumeration.generics,
+        template.bound.as_deref(),
+        template.columns.iter().filter(|field| !field.skip_from),
+        field_bound,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let df = format_ident!("df");
+    let df_rows = tagged_rows_impl(&template, &df, false);
+    let column_body = column_impl(&df, tagged_rows_impl(&template, &df, true));
+
+    // see derive_struct
+    let column_impl = if cfg!(feature = "dtype-struct") {
+        quote! {
+            impl #impl_generics ::polars_derive::FromColumn for #name #ty_generics #where_clause {
+              fn from_column(
+                series: &::polars::series::Series,
+              ) -> Result<
+                Vec<Result<Option<Self>, ::polars_derive::Error>>,
+                ::polars_derive::Error,
+              > {
+                #column_body
+              }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        impl #impl_generics ::polars_derive::FromDataFrame for #name #ty_generics #where_clause {
+          fn iter_df(
+            #df: &polars::frame::DataFrame,
+          ) -> Result<
+            impl Iterator<Item = Result<Self, ::polars_derive::Error>>,
+            ::polars_derive::Error,
+          > {
+            Ok(#df_rows)
+          }
+
+          fn from_df(
+            #df: &polars::frame::DataFrame,
+          ) -> polars::prelude::PolarsResult<Vec<Self>> {
+            Ok(<Self as ::polars_derive::FromDataFrame>::iter_df(#df)?
+              .collect::<Result<_, ::polars_derive::Error>>()?)
+          }
+        }
+
+        #column_impl
+    }
+}
+
+/// Returns the body of `FromColumn::from_column` for structs and tagged enums,
+/// which reads `rows` (see [`rows_impl`]) out of the fields of a struct
+/// column, in a `DataFrame` called `df`. The errors refer to the fields as
+/// `<column>.<field>`, unless the column has no name.
+fn column_impl(df: &syn::Ident, rows: TokenStream2) -> TokenStream2 {
+    quote! {
+        let prefix = match series.name() {
+            "" => String::new(),
+            name => format!("{}.", name),
+        };
+        let column_error = |source| ::polars_derive::Error::Column {
+            column: series.name().to_owned(),
+            source,
+        };
+
+        let fields = series.struct_().map_err(column_error)?.fields().to_vec();
+        let #df = &::polars::frame::DataFrame::new(fields).map_err(column_error)?;
+        let rows = (move || -> Result<_, ::polars_derive::Error> { Ok(#rows) })()
+            .map_err(|err| ::polars_derive::__private::prefix_error(&prefix, err))?;
+
+        Ok(rows
+            .map(|row| row.map_err(|err| ::polars_derive::__private::prefix_error(&prefix, err)))
+            .collect())
+    }
+}
+
+/// Returns Rust code which declares `c_<field>`, an iterator over the items in
+/// the column for this field.
+fn field_iter_init(field: &Column, df: &syn::Ident) -> TokenStream2 {
+    let col_name = &field.name;
+    let var_name = field.var("c");
+
+    if let Some(prefix) = &field.flatten {
+        let rtype = &field.rtype;
+        let sub_df = quote! { ::polars_derive::__private::strip_prefix(#df, #prefix)? };
+
+        // the rows borrow from the columns without the prefix, so they are
+        // collected, but each of them is read on its own
+        let col_expr = quote! {
+            <#rtype as ::polars_derive::FromDataFrame>::iter_df(&#sub_df)
+                .map_err(|err| ::polars_derive::__private::prefix_error(#prefix, err))?
+                .map(|row| {
+                    row.map(Some)
+                        .map_err(|err| ::polars_derive::__private::prefix_error(#prefix, err))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        return quote_spanned! {field.span=> let mut #var_name = #col_expr; };
+    }
+
+    let aliases = &field.aliases;
+    let col_lookup = quote! {
+        ::polars_derive::__private::column(#df, &[#col_name, #(#aliases),*])
+    };
+
+    let col_expr = |col_expr: TokenStream2| match &field.dtype {
+        Some(dtype @ DataType::Datetime(_, _)) if field.convert_from.is_some() => {
+            // the conversion is given the timestamps as they are stored, see
+            // item_dtype
+            let ca = checked_series(col_name, col_expr, dtype, "datetime");
+            quote! { #ca.into_iter() }
+        }
+        Some(dtype) => series_to_rtype(col_name, col_expr, dtype),
+        None => {
+            let rtype = &field.rtype;
+            // a structure whose fields are all None is only missing if the
+            // field can be missing, see FromColumn::from_required_column
+            let method = if field.optional || field.default.is_some() {
+                quote! { from_column }
+            } else {
+                quote! { from_required_column }
+            };
+            quote! {
+                <#rtype as ::polars_derive::FromColumn>::#method(#col_expr)?
+            }
+        }
+    };
+
+    if field.default.is_some() {
+        // if the column is missing, every row gets the default value. to tell
+        // this apart from a null, the items are wrapped in another Option
+        let col = format_ident!("col");
+        let col_expr = col_expr(col.to_token_stream());
+
+        return quote_spanned! {field.span=>
+            let mut #var_name = match #col_lookup.ok() {
+                Some(#col) => Some(#col_expr.into_iter().map(Some)),
+                None => None,
+            }
+            .into_iter()
+            .flatten()
+            .chain(::std::iter::repeat_with(|| None))
+            .take(#df.height());
+        };
+    }
+
+    let col_expr = col_expr(quote! { #col_lookup? });
+
+    quote_spanned! {field.span=> let mut #var_name = #col_expr.into_iter(); }
+}
+
+/// Returns the data type that the items of the column for this field are read
+/// as. The timestamps of a `Datetime` column whose items are converted using
+/// `try_from` or `deserialize_with` are given to the conversion as they are
+/// stored, in the time unit of the column, so they are read as `Int64`.
+fn item_dtype(field: &Column) -> Option<DataType> {
+    match &field.dtype {
+        Some(DataType::Datetime(_, _)) if field.convert_from.is_some() => Some(DataType::Int64),
+        dtype => dtype.clone(),
+    }
+}
+
+/// Returns Rust code which converts `pat_name`, an item from the column for
+/// this field, into the value of the field.
+fn field_getter(field: &Column, pat_name: &syn::Ident) -> TokenStream2 {
+    let dtype = item_dtype(field);
+    let default = match &field.default {
+        Some(default) => default.to_expr(),
+        None => {
+            let getter = item_to_rtype(
+                &field.name,
+                pat_name,
+                dtype.as_ref(),
+                Some(&field.rtype),
+                field.optional,
+                field.borrows(),
+            );
+            return convert_from(field, getter);
+        }
+    };
+
+    // the item is wrapped in another Option which is None if the column is
+    // missing, see field_iter_init
+    let getter = if field.optional {
+        let getter = item_to_rtype(
+            &field.name,
+            pat_name,
+            dtype.as_ref(),
+            Some(&field.rtype),
+            true,
+            field.borrows(),
+        );
+        convert_from(field, getter)
+    } else {
+        let value = format_ident!("v");
+        let getter = item_to_rtype(
+            &field.name,
+            pat_name,
+            dtype.as_ref(),
+            Some(&field.rtype),
+            true,
+            field.borrows(),
+        );
+        let converted = convert_from(field, value.to_token_stream());
 
-        let getter = item_to_rtype(col_name, pat_name, &field.dtype, field.optional);
+        quote! {
+            match #getter {
+                Some(#value) => #converted,
+                None => #default,
+            }
+        }
+    };
 
-        let getter = match &field.convert_from {
-          Some(ConvertFrom::TryFrom { borrow }) => {
-            let getter = if *borrow { quote! { ::std::borrow::Borrow::borrow(&#getter) } } else { getter };
+    quote! {
+        match #pat_name {
+            Some(#pat_name) => #getter,
+            None => #default,
+        }
+    }
+}
+
+/// Returns Rust code which applies the conversion for this field, if any, to
+/// `getter`. If the conversion fails, the code returns early with an error that
+/// refers to the index of the row, which is in a variable called `row`.
+fn convert_from(field: &Column, getter: TokenStream2) -> TokenStream2 {
+    let name = &field.name;
+    let map_err = quote! {
+        .map_err(|err| ::polars_derive::Error::InvalidValue {
+            column: #name.to_owned(),
+            row,
+            source: ::std::convert::Into::into(err),
+        })?
+    };
+
+    match &field.convert_from {
+        Some(ConvertFrom::TryFrom { borrow }) => {
+            let getter = if *borrow {
+                quote! { ::std::borrow::Borrow::borrow(&#getter) }
+            } else {
+                getter
+            };
             let ty = &field.ty;
 
-            quote! { 
-              <#ty as TryFrom<_>>::try_from(#getter)
-                .map_err(|err| ::polars::error::PolarsError::SchemaMismatch(::polars::error::ErrString::from(err.to_string())))?
+            quote! {
+              <#ty as TryFrom<_>>::try_from(#getter)#map_err
             }
-          },
-          Some(ConvertFrom::Custom { fun, borrow }) => {
-            let getter = if *borrow { quote! { &#getter } } else { getter };
+        }
+        Some(ConvertFrom::Custom { fun, borrow }) => {
+            let getter = if *borrow {
+                quote! { &#getter }
+            } else {
+                getter
+            };
             quote! {
-              #fun(#getter).map_err(|err| ::polars::error::PolarsError::SchemaMismatch(::polars::error::ErrString::from(err.to_string())))?
+              #fun(#getter)#map_err
             }
-          },
-          None => getter,
+        }
+        None => getter,
+    }
+}
+
+/// Returns Rust code which will read the rows out of `df`, evaluating to an
+/// iterator over `Result<Self, Error>` which reads one row at a time. The
+/// columns are looked up beforehand, so the code returns early with an error if
+/// any of them are missing. The index of each row is in a variable called
+/// `row`.
+fn rows_impl(template: &Template, df: &syn::Ident) -> TokenStream2 {
+    let fields: Vec<_> = template
+        .fields
+        .iter()
+        .filter(|field| !field.skip_from)
+        .collect();
+
+    let field_iter_ids = fields.iter().map(|field| field.var("c"));
+
+    let field_iter_inits = fields.iter().map(|field| field_iter_init(field, df));
+
+    let field_iter_pat_names: Vec<_> = fields.iter().map(|field| field.var("i")).collect();
+
+    let field_iter_pats = fields
+        .iter()
+        .zip(&field_iter_pat_names)
+        .map(|(field, var_name)| {
+            quote_spanned! {field.span=> Some(#var_name) }
+        });
+
+    let field_iter_getters = template.fields.iter().map(|field| {
+        let member = &field.member;
+        let getter = if field.skip_from {
+            field.default_expr()
+        } else {
+            field_getter(field, &field.var("i"))
         };
 
-        // we throw on a little .into() b/c it's a no-op when it is not needed
         quote_spanned! {field.span=>
-            #value_name: #getter
+            #member: #getter
         }
     });
 
-    quote! {
-        impl ::polars_derive::FromDataFrame for #name {
-          fn from_df(
-            #df: &polars::frame::DataFrame,
-          ) -> Result<Vec<Self>, ::polars::error::PolarsError> {
-            let mut #out = vec![];
+    let row = quote! {
+        Self {
+          #(#field_iter_getters),*
+        }
+    };
 
+    let read_rows = if fields.is_empty() {
+        // there are no columns to iterate over, but there are still rows
+        quote! {
+            (0..#df.height()).map(move |_| -> Result<_, ::polars_derive::Error> { Ok(#row) })
+        }
+    } else {
+        // the getters use `?`, so each row is read inside of a closure
+        quote! {
+            ::std::iter::from_fn(move || match (#(#field_iter_ids.next()),*) {
+                (#(#field_iter_pats),*) => {
+                    let row = next_row;
+                    next_row += 1;
+                    Some((|| -> Result<_, ::polars_derive::Error> { Ok(#row) })())
+                }
+                _ => None,
+            })
+        }
+    };
+
+    quote! {
+        {
             #(#field_iter_inits)*
+            let mut next_row = 0usize;
+
+            #read_rows
+        }
+    }
+}
+
+/// Same as [`rows_impl`], but for enums whose variants have fields. The
+/// variant of each row is read from the tag column, and only the columns which
+/// belong to that variant are read.
+fn tagged_rows_impl(template: &EnumTemplate, df: &syn::Ident, optional_rows: bool) -> TokenStream2 {
+    let tag = &template.tag;
 
-            while let (#(#field_iter_pats),*) = (#(#field_iter_ids.next()),*) {
-              #out.push(Self {
-                #(#field_iter_getters),*
-              })
+    let columns: Vec<_> = template
+        .columns
+        .iter()
+        .filter(|field| !field.skip_from)
+        .collect();
+
+    let field_iter_ids = columns.iter().map(|field| field.var("c"));
+
+    let field_iter_inits = columns.iter().map(|field| field_iter_init(field, df));
+
+    let field_iter_pats = columns.iter().map(|field| {
+        let var_name = field.var("i");
+        quote_spanned! {field.span=> Some(#var_name) }
+    });
+
+    let arms = template.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let variant_name = &variant.name;
+
+        let getters = variant.fields.iter().map(|field| {
+            let member = &field.member;
+
+            if field.skip_from {
+                let default = field.default_expr();
+                return quote_spanned! {field.span=> #member: #default };
             }
 
-            Ok(#out)
-          }
+            // the variable is named after the field that the column was first
+            // seen in, which might be in another variant
+            let col = template
+                .columns
+                .iter()
+                .find(|col| col.name == field.name)
+                .expect("every field should have a column");
+            let getter = field_getter(field, &col.var("i"));
+
+            quote_spanned! {field.span=>
+                #member: #getter
+            }
+        });
+
+        let value = quote! { Self::#ident { #(#getters),* } };
+        let value = if optional_rows {
+            quote! { Some(#value) }
+        } else {
+            value
+        };
+
+        quote! { Some(#variant_name) => #value, }
+    });
+
+    let none_arm = if optional_rows {
+        quote! { None => None, }
+    } else {
+        quote! {
+            None => return Err(::polars_derive::Error::MissingValue {
+                column: #tag.to_owned(),
+                row,
+            }),
+        }
+    };
+
+    quote! {
+        {
+            let mut tags = ::polars_derive::__private::Tags::new(
+                ::polars_derive::__private::column(#df, &[#tag])?,
+            )?;
+            #(#field_iter_inits)*
+            let mut next_row = 0usize;
+
+            ::std::iter::from_fn(move || match (tags.next_tag(), #(#field_iter_ids.next(),)*) {
+                (Some(tag), #(#field_iter_pats,)*) => {
+                    let row = next_row;
+                    next_row += 1;
+                    Some((|| -> Result<_, ::polars_derive::Error> {
+                        Ok(match tag {
+                            #(#arms)*
+                            Some(other) => return Err(::polars_derive::Error::UnknownVariant {
+                                column: #tag.to_owned(),
+                                row,
+                                variant: other.to_owned(),
+                            }),
+                            #none_arm
+                        })
+                    })())
+                }
+                _ => None,
+            })
         }
     }
 }
@@ -94,7 +740,7 @@ pub fn derive(input: TokenStream2) -> TokenStream2 {
 /// Returns Rust code which will convert a Polars Series into a Polars
 /// ChunkedArray, which is necessary to get an iterator with a specific item
 /// data type.
-fn series_to_rtype(inner: impl ToTokens, dtype: &DataType) -> TokenStream2 {
+fn series_to_rtype(name: &str, inner: impl ToTokens, dtype: &DataType) -> TokenStream2 {
     let dtype_method = match dtype {
         DataType::Boolean => "bool",
         DataType::UInt8 => "u8",
@@ -109,42 +755,192 @@ fn series_to_rtype(inner: impl ToTokens, dtype: &DataType) -> TokenStream2 {
         DataType::Float64 => "f64",
         DataType::Utf8 => "utf8",
         DataType::Date => "date",
-        DataType::Datetime(_, _) => "datetime",
-        DataType::Duration(_) => "duration",
+        DataType::Datetime(_, _) => {
+            // the values are paired with the time unit and the time zone of
+            // the column, which might not be the ones in the schema
+            let ca = checked_series(name, inner, dtype, "datetime");
+            return quote! {
+                {
+                    let ca = #ca;
+                    let tu = ca.time_unit();
+                    let tz: Option<::std::sync::Arc<str>> =
+                        ca.time_zone().as_deref().map(::std::convert::From::from);
+                    ca.into_iter().map(move |v| v.map(|v| (v, tu, tz.clone())))
+                }
+            };
+        }
+        DataType::Duration(_) => {
+            // the values are paired with the time unit of the column, which
+            // might not be the one in the schema
+            let ca = checked_series(name, inner, dtype, "duration");
+            return quote! {
+                {
+                    let ca = #ca;
+                    let tu = ca.time_unit();
+                    ca.into_iter().map(move |v| v.map(|v| (v, tu)))
+                }
+            };
+        }
         DataType::Time => "time",
         DataType::Binary => "binary",
         DataType::List(inner_dtype) => {
             let local = format_ident!("l");
-            let inner_converter = series_to_rtype(local.clone(), &*inner_dtype);
+            let inner_converter = series_to_rtype(name, local.clone(), inner_dtype);
             // need to clone b/c otherwise we will get a lifetime error related
-            // to #local (methods like .utf8() are &self)
-            return quote! { #inner.list()?.into_iter().map(|i| { i.map(|#local| { Ok(#inner_converter.clone()) }) }) };
+            // to #local (methods like .utf8() are &self). the items of nested
+            // lists, datetimes and durations are iterators which can't be
+            // cloned, so they are collected instead
+            let owned = match &**inner_dtype {
+                DataType::List(_) | DataType::Datetime(_, _) | DataType::Duration(_) => {
+                    quote! { c.collect::<Vec<_>>() }
+                }
+                // the tuples are already collected
+                #[cfg(feature = "dtype-struct")]
+                DataType::Struct(_) => quote! { c },
+                _ => quote! { c.clone() },
+            };
+            let list = checked_series(name, inner, dtype, "list");
+            return quote! {
+                #list.into_iter().map(|i| {
+                    i.map(|#local| -> Result<_, ::polars_derive::Error> {
+                        let c = #inner_converter;
+                        Ok(#owned)
+                    })
+                })
+            };
+        }
+        #[cfg(feature = "dtype-struct")]
+        DataType::Struct(fields) => {
+            // structs are read into tuples: we read each of the fields and then
+            // zip them back together. we can't return an iterator that borrows
+            // from the fields, so the tuples are collected into a Vec
+            let series_names: Vec<_> = (0..fields.len())
+                .map(|idx| format_ident!("s{idx}"))
+                .collect();
+            let iter_names: Vec<_> = (0..fields.len())
+                .map(|idx| format_ident!("c{idx}"))
+                .collect();
+            let pat_names: Vec<_> = (0..fields.len())
+                .map(|idx| format_ident!("i{idx}"))
+                .collect();
+
+            let iter_inits = fields.iter().zip(&series_names).zip(&iter_names).map(
+                |((field, series_name), iter_name)| {
+                    let field_name = field.name().as_str();
+                    let column = format!("{name}.{field_name}");
+                    let converter = series_to_rtype(&column, series_name, field.data_type());
+                    quote! {
+                        let #series_name = st.field_by_name(#field_name)
+                            .map_err(|_| ::polars_derive::Error::MissingColumn {
+                                column: #column.to_owned(),
+                            })?;
+                        let mut #iter_name = #converter.into_iter();
+                    }
+                },
+            );
+
+            let getters = fields.iter().zip(&pat_names).map(|(field, pat_name)| {
+                let field_name = format!("{name}.{}", field.name());
+                item_to_rtype(
+                    &field_name,
+                    pat_name,
+                    Some(field.data_type()),
+                    None,
+                    false,
+                    false,
+                )
+            });
+
+            let st = checked_series(name, inner, dtype, "struct_");
+            return quote! {
+                {
+                    let st = #st;
+                    #(#iter_inits)*
+
+                    // an element which can't be read is an error for its own
+                    // row, see item_to_rtype
+                    let mut out = vec![];
+                    let mut row = 0usize;
+                    while let (#(Some(#pat_names)),*,) = (#(#iter_names.next()),*,) {
+                        if #(#pat_names.is_none())&&* {
+                            out.push(None);
+                        } else {
+                            out.push(Some((|| -> Result<_, ::polars_derive::Error> {
+                                Ok((#(#getters),*,))
+                            })()));
+                        }
+                        row += 1;
+                    }
+                    out
+                }
+            };
         }
-        _ => unimplemented!("dtype not implemented"),
+        other => return unsupported_dtype(other),
     };
 
-    let dtype_method = format_ident!("{}", dtype_method);
+    checked_series(name, inner, dtype, dtype_method)
+}
 
-    quote! { #inner.#dtype_method()? }
+/// Returns Rust code which calls `method` to convert `inner`, a Polars Series,
+/// into a ChunkedArray of `dtype`, returning early with an error if the Series
+/// has another data type.
+fn checked_series(
+    name: &str,
+    inner: impl ToTokens,
+    dtype: &DataType,
+    method: &str,
+) -> TokenStream2 {
+    let method = format_ident!("{}", method);
+    let expected = dtype_to_expr(dtype);
+
+    quote! {
+        {
+            let s = &#inner;
+            s.#method()
+                .map_err(|_| ::polars_derive::__private::dtype_mismatch(#name, #expected, s))?
+        }
+    }
 }
 
 /// Returns Rust code which will unwrap the data as extracted from the Polars
-/// Series. Useful for list data types, optional data, etc.
+/// Series. Useful for list data types, optional data, etc. `rtype` is the Rust
+/// type of the data, if it is known, which tells whether the items of lists
+/// can be null. If `borrowed` is true, strings and bytes are left borrowed
+/// from the Series.
 fn item_to_rtype(
     name: &str,
     inner: impl ToTokens,
-    dtype: &DataType,
+    dtype: Option<&DataType>,
+    rtype: Option<&syn::Type>,
     optional: bool,
+    borrowed: bool,
 ) -> TokenStream2 {
+    // columns which are read through FromColumn hold the error for each row
+    let inner = match dtype {
+        Some(_) => inner.to_token_stream(),
+        None => quote! { #inner? },
+    };
+
     // if necessary, we will run the Option through a closure that converts it
     // to the desired type using map()
 
     // this is a Option((TokenStream, bool)) where the bool represents whether the converter is fallible
     let converter = match dtype {
-        DataType::List(inner_dtype) => {
+        Some(DataType::List(inner_dtype)) => {
             let local = format_ident!("i");
             let inner_name = format!("{name}.<item>");
-            let inner_converter = item_to_rtype(&inner_name, local.clone(), &*inner_dtype, false);
+            let (item_rtype, item_optional) = match rtype.and_then(list_item_type) {
+                Some((item_rtype, item_optional)) => (Some(item_rtype), item_optional),
+                None => (None, false),
+            };
+            let inner_converter = item_to_rtype(
+                &inner_name,
+                local.clone(),
+                Some(inner_dtype),
+                item_rtype,
+                item_optional,
+                false,
+            );
 
             // our iterator gives a Result b/c converting the individual items
             // of this List might have failed; so we need to map through the
@@ -157,19 +953,66 @@ fn item_to_rtype(
                 quote! {
                     |r| r.and_then(|l| {
                         l.into_iter()
-                            .map(|#local| -> ::polars::error::PolarsResult<_> { Ok(#inner_converter) })
+                            .map(|#local| -> Result<_, ::polars_derive::Error> {
+                                let v = #inner_converter;
+                                Ok(v)
+                            })
                             .collect::<Result<Vec<_>, _>>()
                     })
                 },
                 true,
             ))
         }
-        DataType::Utf8 => {
-            // for now we only support owned strings for FromDataFrame
+        Some(DataType::Datetime(_, _)) => {
+            let deserialize = deserialize_datetime(rtype);
+            Some((
+                quote! {
+                    |(ts, tu, tz)| #deserialize.map_err(|err| ::polars_derive::Error::InvalidValue {
+                        column: #name.to_owned(),
+                        row,
+                        source: ::std::convert::Into::into(err),
+                    })
+                },
+                true,
+            ))
+        }
+        Some(dtype @ (DataType::Date | DataType::Time | DataType::Duration(_))) => {
+            let (pat, convert) = match dtype {
+                DataType::Date => (
+                    quote! { v },
+                    quote! { ::polars_derive::helpers::FromDate::from_date(v) },
+                ),
+                DataType::Time => (
+                    quote! { v },
+                    quote! { ::polars_derive::helpers::FromTime::from_time(v) },
+                ),
+                _ => (
+                    quote! { (v, tu) },
+                    quote! { ::polars_derive::helpers::FromDuration::from_duration(v, tu) },
+                ),
+            };
+            Some((
+                quote! {
+                    |#pat| #convert.map_err(|err| ::polars_derive::Error::InvalidValue {
+                        column: #name.to_owned(),
+                        row,
+                        source: ::std::convert::Into::into(err),
+                    })
+                },
+                true,
+            ))
+        }
+        #[cfg(feature = "dtype-struct")]
+        Some(DataType::Struct(_)) => {
+            // the tuple is a Result b/c reading its elements might have failed
+            Some((quote! { |r| r }, true))
+        }
+        Some(DataType::Utf8 | DataType::Binary) if borrowed => None,
+        Some(DataType::Utf8) => {
             // Polars gives us a &str so we call to_owned()
             Some((quote! { |s| s.to_owned() }, false))
         }
-        DataType::Binary => {
+        Some(DataType::Binary) => {
             // convert &[u8] to Vec<u8>
             Some((quote! { |s| Vec::from(s) }, false))
         }
@@ -188,16 +1031,46 @@ fn item_to_rtype(
         inner.to_token_stream()
     };
 
-    let getter = if optional {
-        quote! {
-          #getter
-        }
+    if optional {
+        getter
     } else {
-        let error_msg = format!("unexpected missing data for field {name}");
         quote! {
-          #getter.ok_or(::polars::error::PolarsError::NoData(::polars::error::ErrString::from(#error_msg)))?
+          #getter.ok_or_else(|| ::polars_derive::Error::MissingValue {
+              column: #name.to_owned(),
+              row,
+          })?
         }
+    }
+}
+
+/// Returns Rust code which converts `ts`, a timestamp in the time unit `tu`
+/// of the column, into `rtype`, evaluating to a `Result`. `rtype` is a
+/// `DateTime<Tz>` or an `OffsetDateTime` which is read in `tz`, the time zone
+/// of the column (or `UTC` if it has none), or otherwise a type which
+/// implements `FromTimestamp`.
+fn deserialize_datetime(rtype: Option<&syn::Type>) -> TokenStream2 {
+    let tz = quote! { tz.as_deref().unwrap_or("UTC") };
+
+    #[cfg(feature = "time")]
+    if rtype.is_some_and(crate::common::is_offset_datetime) {
+        return quote! { ::polars_derive::helpers::deserialize_offset_datetime(ts, tu, #tz) };
+    }
+
+    let zone = match rtype.and_then(datetime_zone) {
+        Some(zone) => zone,
+        None => return quote! { ::polars_derive::helpers::FromTimestamp::from_timestamp(ts, tu) },
     };
 
-    getter
+    let is_zone = |name: &str| {
+        matches!(zone, syn::Type::Path(zone)
+            if zone.path.segments.last().is_some_and(|segment| segment.ident == name))
+    };
+
+    if is_zone("Utc") {
+        quote! { ::polars_derive::helpers::deserialize_datetime_utc(ts, tu) }
+    } else if is_zone("FixedOffset") {
+        quote! { ::polars_derive::helpers::deserialize_datetime_fixed(ts, tu, #tz) }
+    } else {
+        quote! { ::polars_derive::helpers::deserialize_datetime_tz::<#zone>(ts, tu, #tz) }
+    }
 }
@@ -3,112 +3,923 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
 
-use crate::common::{dtype_to_expr, rtype_for_dtype, ConvertInto, Template};
+use crate::common::{
+    bounded_generics, dtype_to_expr, list_item_type, mentions_params, rtype_for_dtype,
+    time_unit_to_expr, unsupported_dtype, Column, ConvertInto, EnumTemplate, Input, Template,
+};
 
 pub fn derive(input: TokenStream2) -> TokenStream2 {
-    let template: Template = match syn::parse2(input) {
-        Ok(template) => template,
-        Err(err) => return err.to_compile_error().into(),
+    match syn::parse2(input) {
+        Ok(Input::Struct(template)) if template.transparent => derive_transparent(template),
+        Ok(Input::Struct(template)) => derive_struct(template),
+        Ok(Input::Enum(template)) if template.is_tagged() => derive_tagged_enum(template),
+        Ok(Input::Enum(template)) => derive_enum(template),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+/// Returns the bound that the type of this field needs in order to be stored.
+fn field_bound(field: &Column) -> Option<TokenStream2> {
+    // structures only implement IntoColumn with the dtype-struct feature
+    if field.flatten.is_some() && cfg!(feature = "dtype-struct") {
+        Some(quote! { ::polars_derive::IntoDataFrame + ::polars_derive::IntoColumn })
+    } else if field.flatten.is_some() {
+        Some(quote! { ::polars_derive::IntoDataFrame })
+    } else if field.dtype.is_none() {
+        Some(quote! { ::polars_derive::IntoColumn })
+    } else {
+        None
+    }
+}
+
+/// Returns true if the value of this field has to be cloned in order to store
+/// it when the rows are only borrowed.
+fn clones_value(field: &Column) -> bool {
+    match (&field.convert_into, &field.dtype) {
+        (Some(ConvertInto::AsRef(_) | ConvertInto::Custom { borrow: true, .. }), _) => false,
+        (Some(_), _) => true,
+        (None, Some(dtype)) => borrowed_type(dtype, &field.rtype).is_none(),
+        (None, None) => false,
+    }
+}
+
+/// Requires the types of the generic fields which are cloned when the rows are
+/// borrowed to implement Clone, unless the bounds are given explicitly.
+fn add_clone_bounds<'a>(
+    generics: &mut syn::Generics,
+    bound: Option<&[syn::WherePredicate]>,
+    fields: impl IntoIterator<Item = &'a Column>,
+) {
+    if bound.is_some() {
+        return;
+    }
+
+    let cloned: Vec<_> = fields
+        .into_iter()
+        .filter(|field| clones_value(field) && mentions_params(&field.ty, generics))
+        .map(|field| &field.ty)
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for ty in cloned {
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { #ty: ::std::clone::Clone });
+    }
+}
+
+/// Enums whose variants have no fields are stored in a single column, so they
+/// only implement IntoColumn.
+fn derive_enum(template: EnumTemplate) -> TokenStream2 {
+    let name = template.enumeration.ident.clone();
+    let generics = bounded_generics(
+        &template.enumeration.generics,
+        template.bound.as_deref(),
+        [],
+        field_bound,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let arms: Vec<_> = template
+        .variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+            let name = &variant.name;
+            quote! { Self::#ident => #name }
+        })
+        .collect();
+
+    let (dtype, cast) = if template.categorical {
+        (
+            quote! { ::polars::datatypes::DataType::Categorical(None) },
+            quote! {
+                .cast(&::polars::datatypes::DataType::Categorical(None))
+                .expect("strings should be convertible to categoricals")
+            },
+        )
+    } else {
+        (quote! { ::polars::datatypes::DataType::Utf8 }, quote! {})
     };
 
-    let structure = template.structure;
-    let name = structure.ident.clone();
+    quote! {
+        impl #impl_generics ::polars_derive::IntoColumn for #name #ty_generics #where_clause {
+            fn dtype() -> ::polars::datatypes::DataType {
+                #dtype
+            }
 
-    let series_impl = {
-        let field_vector_names: Vec<_> = template
-            .fields
-            .iter()
-            .map(|field| format_ident!("v_{}", field.name))
-            .collect();
-
-        let field_vector_decls =
-            template
-                .fields
-                .iter()
-                .zip(&field_vector_names)
-                .map(|(field, var_name)| {
-                    let target_ty = rtype_for_dtype(&field.dtype);
-
-                    let target_ty = match &field.convert_into {
-                        Some(ConvertInto::AsRef(ty)) => ty,
-                        Some(ConvertInto::Into(ty)) => ty,
-                        Some(ConvertInto::Custom { .. }) => &target_ty,
-                        None => &field.ty,
-                    };
-
-                    quote! { let mut #var_name: Vec<#target_ty> = vec![]; }
-                });
-
-        let field_vector_fillers =
-            template
-                .fields
-                .iter()
-                .zip(&field_vector_names)
-                .map(|(field, var_name)| {
-                    let name = &field.name;
-                    let field_name = quote::format_ident!("{}", name);
-
-                    let converter = match &field.convert_into {
-                        Some(ConvertInto::AsRef(_)) => quote! { item.#field_name.as_ref() },
-                        Some(ConvertInto::Into(_)) => quote! { item.#field_name.into() },
-                        Some(ConvertInto::Custom { fun, borrow }) => {
-                            if *borrow {
-                                quote! { #fun(&item.#field_name) }
-                            } else {
-                                quote! { #fun(item.#field_name) }
-                            }
-                        }
-                        None => quote! { item.#field_name },
-                    };
+            fn into_column(
+                name: &str,
+                values: Vec<Option<Self>>,
+            ) -> ::polars::series::Series {
+                let v = values
+                    .into_iter()
+                    .map(|value| value.map(|value| match value { #(#arms),* }))
+                    .collect::<Vec<Option<&str>>>();
+
+                <::polars::series::Series as ::polars::prelude::NamedFrom<_, _>>::new(
+                    name,
+                    v.as_slice(),
+                )
+                #cast
+            }
+
+            fn to_column(
+                name: &str,
+                values: Vec<Option<&Self>>,
+            ) -> ::polars::series::Series {
+                let v = values
+                    .into_iter()
+                    .map(|value| value.map(|value| match value { #(#arms),* }))
+                    .collect::<Vec<Option<&str>>>();
+
+                <::polars::series::Series as ::polars::prelude::NamedFrom<_, _>>::new(
+                    name,
+                    v.as_slice(),
+                )
+                #cast
+            }
+        }
+    }
+}
+
+/// Transparent structs are stored in the same way as their only field, so they
+/// only implement IntoColumn.
+fn derive_transparent(template: Template) -> TokenStream2 {
+    let name = template.structure.ident.clone();
+    let field = &template.fields[0];
+    let generics = bounded_generics(
+        &template.structure.generics,
+        template.bound.as_deref(),
+        [field],
+        |_| Some(quote! { ::polars_derive::IntoColumn }),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let ty = &field.ty;
+    let member = &field.member;
+
+    quote! {
+        impl #impl_generics ::polars_derive::IntoColumn for #name #ty_generics #where_clause {
+            fn dtype() -> ::polars::datatypes::DataType {
+                <#ty as ::polars_derive::IntoColumn>::dtype()
+            }
+
+            fn into_column(
+                name: &str,
+                values: Vec<Option<Self>>,
+            ) -> ::polars::series::Series {
+                <#ty as ::polars_derive::IntoColumn>::into_column(
+                    name,
+                    values
+                        .into_iter()
+                        .map(|value| value.map(|value| value.#member))
+                        .collect(),
+                )
+            }
+
+            fn to_column(
+                name: &str,
+                values: Vec<Option<&Self>>,
+            ) -> ::polars::series::Series {
+                <#ty as ::polars_derive::IntoColumn>::to_column(
+                    name,
+                    values
+                        .into_iter()
+                        .map(|value| value.map(|value| &value.#member))
+                        .collect(),
+                )
+            }
+
+            fn try_into_column(
+                name: &str,
+                values: Vec<Option<Self>>,
+            ) -> ::polars::error::PolarsResult<::polars::series::Series> {
+                <#ty as ::polars_derive::IntoColumn>::try_into_column(
+                    name,
+                    values
+                        .into_iter()
+                        .map(|value| value.map(|value| value.#member))
+                        .collect(),
+                )
+            }
+
+            fn try_to_column(
+                name: &str,
+                values: Vec<Option<&Self>>,
+            ) -> ::polars::error::PolarsResult<::polars::series::Series> {
+                <#ty as ::polars_derive::IntoColumn>::try_to_column(
+                    name,
+                    values
+                        .into_iter()
+                        .map(|value| value.map(|value| &value.#member))
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+fn derive_struct(template: Template) -> TokenStream2 {
+    let name = template.structure.ident.clone();
+    let mut generics = bounded_generics(
+        &template.structure.generics,
+        template.bound.as_deref(),
+        template.fields.iter().filter(|field| !field.skip_into),
+        field_bound,
+    );
+    add_clone_bounds(
+        &mut generics,
+        template.bound.as_deref(),
+        template.fields.iter().filter(|field| !field.skip_into),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let rows = format_ident!("rows");
+    let df_series = series_impl(&template, &rows, false, false);
+    let column_series = series_impl(&template, &rows, true, false);
+    let df_ref_series = series_impl(&template, &rows, false, true);
+    let column_ref_series = series_impl(&template, &rows, true, true);
+
+    let field_schema_decls = template
+        .fields
+        .iter()
+        .filter(|field| !field.skip_into)
+        .map(field_schema);
+
+    let schema = quote! {
+        ::polars::prelude::Schema::from(
+            ::std::iter::empty::<::polars::datatypes::Field>()
+                #(.chain(#field_schema_decls))*
+        )
+    };
+
+    let ty = quote! { #name #ty_generics };
+
+    table_impls(
+        quote! { #impl_generics },
+        ty,
+        where_clause,
+        schema,
+        [df_series, df_ref_series, column_series, column_ref_series],
+    )
+}
+
+/// Enums whose variants have fields are stored as a tag column containing the
+/// names of the variants, followed by the columns of all of the variants.
+fn derive_tagged_enum(template: EnumTemplate) -> TokenStream2 {
+    let name = template.enumeration.ident.clone();
+    let mut generics = bounded_generics(
+        &template.enumeration.generics,
+        template.bound.as_deref(),
+        template.columns.iter().filter(|field| !field.skip_into),
+        field_bound,
+    );
+    add_clone_bounds(
+        &mut generics,
+        template.bound.as_deref(),
+        template.columns.iter().filter(|field| !field.skip_into),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let rows = format_ident!("rows");
+    let df_series = tagged_series_impl(&template, &rows, false, false);
+    let column_series = tagged_series_impl(&template, &rows, true, false);
+    let df_ref_series = tagged_series_impl(&template, &rows, false, true);
+    let column_ref_series = tagged_series_impl(&template, &rows, true, true);
+
+    let tag = &template.tag;
+    let tag_dtype = if template.categorical {
+        quote! { ::polars::datatypes::DataType::Categorical(None) }
+    } else {
+        quote! { ::polars::datatypes::DataType::Utf8 }
+    };
+
+    let field_schema_decls = template
+        .columns
+        .iter()
+        .filter(|field| !field.skip_into)
+        .map(field_schema);
+
+    let schema = quote! {
+        ::polars::prelude::Schema::from(
+            ::std::iter::once(::polars::datatypes::Field::new(#tag, #tag_dtype))
+                #(.chain(#field_schema_decls))*
+        )
+    };
+
+    let ty = quote! { #name #ty_generics };
+
+    table_impls(
+        quote! { #impl_generics },
+        ty,
+        where_clause,
+        schema,
+        [df_series, df_ref_series, column_series, column_ref_series],
+    )
+}
+
+/// Returns the impls of IntoDataFrame and IntoColumn for structs and tagged
+/// enums, which are stored in the same way. `series` holds the code which
+/// builds the Series out of the rows, see [`series_impl`], for owned rows,
+/// borrowed rows, optional rows and borrowed optional rows respectively.
+fn table_impls(
+    impl_generics: TokenStream2,
+    ty: TokenStream2,
+    where_clause: Option<&syn::WhereClause>,
+    schema: TokenStream2,
+    series: [TokenStream2; 4],
+) -> TokenStream2 {
+    let rows = format_ident!("rows");
+    let [df_series, df_ref_series, column_series, column_ref_series] = series;
+
+    // structures are stored in Struct columns, which need the dtype-struct
+    // feature
+    let column_impl = if cfg!(feature = "dtype-struct") {
+        quote! {
+            impl #impl_generics ::polars_derive::IntoColumn for #ty #where_clause {
+                fn dtype() -> ::polars::datatypes::DataType {
+                    ::polars::datatypes::DataType::Struct(
+                        <Self as ::polars_derive::IntoDataFrame>::schema().iter_fields().collect()
+                    )
+                }
+
+                fn into_column(
+                    name: &str,
+                    values: Vec<Option<Self>>,
+                ) -> ::polars::series::Series {
+                    Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+                }
+
+                fn to_column(
+                    name: &str,
+                    values: Vec<Option<&Self>>,
+                ) -> ::polars::series::Series {
+                    Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+                }
+
+                fn try_into_column(
+                    name: &str,
+                    #rows: Vec<Option<Self>>,
+                ) -> ::polars::error::PolarsResult<::polars::series::Series> {
+                    let fields: ::polars::error::PolarsResult<Vec<::polars::series::Series>> =
+                        { #column_series };
+                    let fields = fields?;
+
+                    Ok(::polars::prelude::IntoSeries::into_series(
+                        ::polars::prelude::StructChunked::new(name, &fields)?,
+                    ))
+                }
+
+                fn try_to_column(
+                    name: &str,
+                    #rows: Vec<Option<&Self>>,
+                ) -> ::polars::error::PolarsResult<::polars::series::Series> {
+                    let fields: ::polars::error::PolarsResult<Vec<::polars::series::Series>> =
+                        { #column_ref_series };
+                    let fields = fields?;
+
+                    Ok(::polars::prelude::IntoSeries::into_series(
+                        ::polars::prelude::StructChunked::new(name, &fields)?,
+                    ))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        impl #impl_generics ::polars_derive::IntoDataFrame for #ty #where_clause {
+            fn schema() -> ::polars::prelude::Schema {
+                #schema
+            }
+
+            fn into_series(
+                #rows: impl Iterator<Item = Self>,
+            ) -> Vec<::polars::series::Series> {
+                Self::try_into_series(#rows).unwrap_or_else(|err| panic!("{}", err))
+            }
+
+            fn to_series<'rows>(
+                #rows: impl Iterator<Item = &'rows Self>,
+            ) -> Vec<::polars::series::Series>
+            where
+                Self: 'rows,
+            {
+                Self::try_to_series(#rows).unwrap_or_else(|err| panic!("{}", err))
+            }
+
+            fn try_into_series(
+                #rows: impl Iterator<Item = Self>,
+            ) -> ::polars::error::PolarsResult<Vec<::polars::series::Series>> {
+                #df_series
+            }
+
+            fn try_to_series<'rows>(
+                #rows: impl Iterator<Item = &'rows Self>,
+            ) -> ::polars::error::PolarsResult<Vec<::polars::series::Series>>
+            where
+                Self: 'rows,
+            {
+                #df_ref_series
+            }
+        }
+
+        #column_impl
+    }
+}
+
+/// Returns Rust code which evaluates to an iterator over the fields of the
+/// schema for this column.
+fn field_schema(field: &Column) -> TokenStream2 {
+    let field_name = &field.name;
+    let rtype = &field.rtype;
+
+    if let Some(prefix) = &field.flatten {
+        return quote_spanned! {field.ty.span()=>
+            <#rtype as ::polars_derive::IntoDataFrame>::schema()
+                .iter_fields()
+                .map(|field| {
+                    ::polars::datatypes::Field::new(
+                        &format!("{}{}", #prefix, field.name()),
+                        field.data_type().clone(),
+                    )
+                })
+        };
+    }
+
+    let dtype = match &field.dtype {
+        Some(dtype) => dtype_to_expr(dtype),
+        None => quote! { <#rtype as ::polars_derive::IntoColumn>::dtype() },
+    };
+    quote_spanned! {field.ty.span()=>
+      ::std::iter::once(::polars::datatypes::Field::new(#field_name, #dtype))
+    }
+}
+
+/// Returns the type of the items of the vector which the data for this column
+/// is collected into. If `borrowed` is true, the rows are only borrowed, and
+/// the data is borrowed from them where possible.
+fn field_vector_type(field: &Column, optional_rows: bool, borrowed: bool) -> TokenStream2 {
+    let rtype = &field.rtype;
+
+    match &field.dtype {
+        Some(dtype) => {
+            let target_ty = rtype_for_dtype(dtype);
+
+            let target_ty = match &field.convert_into {
+                Some(ConvertInto::AsRef(ty)) => ty.to_token_stream(),
+                Some(ConvertInto::Into(ty) | ConvertInto::TryInto(ty)) => ty.to_token_stream(),
+                Some(ConvertInto::Custom { .. }) => target_ty.to_token_stream(),
+                None if borrowed => match borrowed_type(dtype, rtype) {
+                    Some(ty) if field.optional => quote! { Option<#ty> },
+                    Some(ty) => ty,
+                    None => field.ty.to_token_stream(),
+                },
+                None => field.ty.to_token_stream(),
+            };
+
+            if optional_rows && !field.optional {
+                quote! { Option<#target_ty> }
+            } else {
+                target_ty
+            }
+        }
+        None if field.flatten.is_some() && !optional_rows => {
+            if borrowed {
+                quote! { &#rtype }
+            } else {
+                rtype.to_token_stream()
+            }
+        }
+        None => {
+            // nested columns are always built from Options, see
+            // IntoColumn::into_column
+            if borrowed {
+                quote! { Option<&#rtype> }
+            } else {
+                quote! { Option<#rtype> }
+            }
+        }
+    }
+}
+
+/// Returns the borrowed type that the data for a column of type `dtype` can be
+/// stored as, if Polars can build a Series out of it. Otherwise, the data has
+/// to be cloned out of the rows.
+fn borrowed_type(dtype: &DataType, rtype: &syn::Type) -> Option<TokenStream2> {
+    fn has_struct(dtype: &DataType) -> bool {
+        match dtype {
+            #[cfg(feature = "dtype-struct")]
+            DataType::Struct(_) => true,
+            DataType::List(inner) => has_struct(inner),
+            _ => false,
+        }
+    }
+
+    match dtype {
+        DataType::Utf8 => Some(quote! { &str }),
+        DataType::Binary => Some(quote! { &[u8] }),
+        DataType::List(inner) if !has_struct(inner) => Some(quote! { &#rtype }),
+        _ => None,
+    }
+}
+
+/// Returns Rust code which converts `value`, the value of this field, into an
+/// item of the vector which the data for this column is collected into. If
+/// `borrowed` is true, `value` is a reference to the value of this field. If
+/// the conversion is fallible, the code returns early with an error that
+/// refers to the index of the row, which is in a variable called `row`.
+fn field_vector_item(
+    field: &Column,
+    value: TokenStream2,
+    optional_rows: bool,
+    borrowed: bool,
+) -> TokenStream2 {
+    let name = &field.name;
+
+    // the value is only needed by reference in order to convert it, so it is
+    // cloned if the rows are borrowed and the conversion needs ownership
+    let owned = if borrowed {
+        quote_spanned! {field.span=> ::std::clone::Clone::clone(#value) }
+    } else {
+        value.clone()
+    };
+
+    let map_err = quote! {
+        .map_err(|err| ::polars_derive::__private::conversion_error(#name, row, err))?
+    };
+
+    let converter = match &field.convert_into {
+        Some(ConvertInto::AsRef(_)) => quote! { #value.as_ref() },
+        Some(ConvertInto::Into(_)) => quote! { #owned.into() },
+        Some(ConvertInto::TryInto(ty)) => quote! {
+            <_ as ::std::convert::TryInto<#ty>>::try_into(#owned)#map_err
+        },
+        Some(ConvertInto::Custom {
+            fun,
+            borrow,
+            fallible,
+        }) => {
+            let converted = match (borrow, borrowed) {
+                (true, true) => quote! { #fun(#value) },
+                (true, false) => quote! { #fun(&#value) },
+                (false, _) => quote! { #fun(#owned) },
+            };
+
+            if *fallible {
+                quote! { #converted #map_err }
+            } else {
+                converted
+            }
+        }
+        None if borrowed => match &field.dtype {
+            Some(dtype) => match (borrowed_type(dtype, &field.rtype), dtype) {
+                (Some(_), DataType::Utf8) if field.optional => {
+                    quote! { #value.as_ref().map(::std::convert::AsRef::<str>::as_ref) }
+                }
+                (Some(_), DataType::Utf8) => {
+                    quote! { ::std::convert::AsRef::<str>::as_ref(#value) }
+                }
+                (Some(_), DataType::Binary) if field.optional => {
+                    quote! { #value.as_ref().map(::std::convert::AsRef::<[u8]>::as_ref) }
+                }
+                (Some(_), DataType::Binary) => {
+                    quote! { ::std::convert::AsRef::<[u8]>::as_ref(#value) }
+                }
+                (Some(_), _) if field.optional => quote! { #value.as_ref() },
+                (Some(_), _) => value,
+                (None, _) => owned,
+            },
+            None if field.optional => quote! { #value.as_ref() },
+            None => value,
+        },
+        None => value,
+    };
+
+    let nested = field.dtype.is_none() && field.flatten.is_none();
+    if (optional_rows || nested) && !field.optional {
+        quote! { Some(#converter) }
+    } else {
+        converter
+    }
+}
+
+/// Returns Rust code which evaluates to an iterator over the Series that are
+/// built from `var_name`, the vector which the data for this column was
+/// collected into.
+fn field_series(
+    field: &Column,
+    var_name: &syn::Ident,
+    optional_rows: bool,
+    borrowed: bool,
+) -> TokenStream2 {
+    let name = &field.name;
+    let rtype = &field.rtype;
+
+    // nested structures might fail to convert as well
+    let (into_series, into_column) = if borrowed {
+        (
+            format_ident!("try_to_series"),
+            format_ident!("try_to_column"),
+        )
+    } else {
+        (
+            format_ident!("try_into_series"),
+            format_ident!("try_into_column"),
+        )
+    };
+
+    if let Some(prefix) = &field.flatten {
+        if optional_rows && !cfg!(feature = "dtype-struct") {
+            return syn::Error::new(
+                field.span,
+                "flattened fields of enums can only be used with the `dtype-struct` feature of \
+                 polars-derive",
+            )
+            .to_compile_error();
+        }
+
+        let series = if optional_rows {
+            // the rows of the nested structure might also be missing, so we
+            // build a struct column and then take it apart again
+            quote! {
+                <#rtype as ::polars_derive::IntoColumn>::#into_column(#name, #var_name)?
+                    .struct_()
+                    .expect("nested structures should be stored as structs")
+                    .fields()
+                    .to_vec()
+            }
+        } else {
+            quote! {
+                <#rtype as ::polars_derive::IntoDataFrame>::#into_series(#var_name.into_iter())?
+            }
+        };
+
+        return quote_spanned! {field.ty.span()=>
+            #series.into_iter().map(|mut s| {
+                let name = format!("{}{}", #prefix, s.name());
+                s.rename(&name);
+                s
+            })
+        };
+    }
+
+    let series = match &field.dtype {
+        Some(dtype) => vec_to_series(
+            name,
+            var_name,
+            dtype,
+            Some(&field.rtype),
+            optional_rows || field.optional,
+            false,
+        ),
+        None => quote_spanned! {field.ty.span()=>
+            <#rtype as ::polars_derive::IntoColumn>::#into_column(#name, #var_name)?
+        },
+    };
 
-                    quote_spanned! {field.span=>
-                        #var_name.push(#converter);
+    quote! { ::std::iter::once(#series) }
+}
+
+/// Returns Rust code which will convert `rows` into a list of Polars Series,
+/// one per field, evaluating to a `PolarsResult<Vec<Series>>`. If
+/// `optional_rows` is true, `rows` yields `Option<Self>` and
+/// a `None` is stored as a null in every column. If `borrowed` is true, `rows`
+/// yields `&Self` (or `Option<&Self>`) instead.
+fn series_impl(
+    template: &Template,
+    rows: &syn::Ident,
+    optional_rows: bool,
+    borrowed: bool,
+) -> TokenStream2 {
+    let fields: Vec<_> = template
+        .fields
+        .iter()
+        .filter(|field| !field.skip_into)
+        .collect();
+
+    let field_vector_names: Vec<_> = fields.iter().map(|field| field.var("v")).collect();
+
+    let field_vector_decls = fields
+        .iter()
+        .zip(&field_vector_names)
+        .map(|(field, var_name)| {
+            let target_ty = field_vector_type(field, optional_rows, borrowed);
+            quote! { let mut #var_name: Vec<#target_ty> = vec![]; }
+        });
+
+    let field_vector_fillers = fields
+        .iter()
+        .zip(&field_vector_names)
+        .map(|(field, var_name)| {
+            let member = &field.member;
+            let value = if borrowed {
+                quote! { (&item.#member) }
+            } else {
+                quote! { item.#member }
+            };
+            let item = field_vector_item(field, value, optional_rows, borrowed);
+
+            quote_spanned! {field.span=>
+                #var_name.push(#item);
+            }
+        });
+
+    let series_decls = fields
+        .iter()
+        .zip(&field_vector_names)
+        .map(|(field, var_name)| field_series(field, var_name, optional_rows, borrowed));
+
+    let fill_rows = if fields.is_empty() {
+        // nothing to store, so there is no need to look at the rows
+        quote! { drop(#rows); }
+    } else if optional_rows {
+        let rows_loop = rows_loop(&fields, rows);
+        quote! {
+            #rows_loop {
+                match item {
+                    Some(item) => {
+                        #(#field_vector_fillers)*
                     }
-                });
+                    None => {
+                        #(#field_vector_names.push(None);)*
+                    }
+                }
+            }
+        }
+    } else {
+        let rows_loop = rows_loop(&fields, rows);
+        quote! {
+            #rows_loop {
+                #(#field_vector_fillers)*
+            }
+        }
+    };
+
+    quote! {
+        #(#field_vector_decls)*
+
+        #fill_rows
+
+        Ok(::std::iter::empty::<::polars::series::Series>()
+            #(.chain(#series_decls))*
+            .collect::<Vec<_>>())
+    }
+}
+
+/// Returns the head of the loop over `rows`, which also keeps track of the
+/// index of the row if any of the conversions can fail.
+fn rows_loop(fields: &[&Column], rows: &syn::Ident) -> TokenStream2 {
+    let fallible = fields.iter().any(|field| {
+        field
+            .convert_into
+            .as_ref()
+            .is_some_and(ConvertInto::is_fallible)
+    });
+
+    if fallible {
+        quote! { for (row, item) in ::std::iter::IntoIterator::into_iter(#rows).enumerate() }
+    } else {
+        quote! { for item in #rows }
+    }
+}
 
-        let series_decls = template
+/// Same as [`series_impl`], but for enums whose variants have fields. The
+/// first Series holds the names of the variants, and the columns that belong
+/// to other variants are null.
+fn tagged_series_impl(
+    template: &EnumTemplate,
+    rows: &syn::Ident,
+    optional_rows: bool,
+    borrowed: bool,
+) -> TokenStream2 {
+    let tag = &template.tag;
+
+    let columns: Vec<_> = template
+        .columns
+        .iter()
+        .filter(|field| !field.skip_into)
+        .collect();
+
+    let field_vector_names: Vec<_> = columns.iter().map(|field| field.var("v")).collect();
+
+    // every column is missing in the rows of the variants that don't have it,
+    // so all of the vectors hold Options
+    let field_vector_decls = columns
+        .iter()
+        .zip(&field_vector_names)
+        .map(|(field, var_name)| {
+            let target_ty = field_vector_type(field, true, borrowed);
+            quote! { let mut #var_name: Vec<#target_ty> = vec![]; }
+        });
+
+    let arms = template.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let variant_name = &variant.name;
+
+        let bindings = variant
             .fields
+            .iter()
+            .filter(|field| !field.skip_into)
+            .map(|field| {
+                let member = &field.member;
+                let binding = field.var("f");
+                quote! { #member: #binding }
+            });
+
+        let fillers = columns
             .iter()
             .zip(&field_vector_names)
-            .map(|(field, var_name)| vec_to_series(&field.name, var_name, &field.dtype));
+            .map(|(col, var_name)| {
+                match variant.fields.iter().find(|field| field.name == col.name) {
+                    Some(field) => {
+                        let binding = field.var("f");
+                        // if the rows are borrowed, so are the bindings
+                        let item = field_vector_item(field, quote! { #binding }, true, borrowed);
+                        quote_spanned! {field.span=> #var_name.push(#item); }
+                    }
+                    None => quote! { #var_name.push(None); },
+                }
+            });
 
-        quote! {
-            #(#field_vector_decls)*
+        let pat = if optional_rows {
+            quote! { Some(Self::#ident { #(#bindings,)* .. }) }
+        } else {
+            quote! { Self::#ident { #(#bindings,)* .. } }
+        };
 
-            for item in rows {
-                #(#field_vector_fillers)*
+        quote! {
+            #pat => {
+                tags.push(Some(#variant_name));
+                #(#fillers)*
             }
+        }
+    });
 
-            vec![
-                #(#series_decls),*
-            ]
+    let none_arm = if optional_rows {
+        quote! {
+            None => {
+                tags.push(None);
+                #(#field_vector_names.push(None);)*
+            }
         }
+    } else {
+        quote! {}
     };
 
-    let field_schema_decls = template.fields.iter().map(|field| {
-        let field_name = &field.name;
-        let dtype = dtype_to_expr(&field.dtype);
-        quote_spanned! {field.ty.span()=>
-          ::polars::datatypes::Field::new(#field_name, #dtype)
+    let series_decls = columns
+        .iter()
+        .zip(&field_vector_names)
+        .map(|(field, var_name)| field_series(field, var_name, true, borrowed));
+
+    let cast = if template.categorical {
+        quote! {
+            .cast(&::polars::datatypes::DataType::Categorical(None))
+            .expect("strings should be convertible to categoricals")
         }
-    });
+    } else {
+        quote! {}
+    };
+
+    let rows_loop = rows_loop(&columns, rows);
 
     quote! {
-        impl ::polars_derive::IntoDataFrame for #name {
-            fn schema() -> ::polars::prelude::Schema {
-              ::polars::prelude::Schema::from([
-                #(#field_schema_decls),*
-              ].into_iter())
-            }
+        let mut tags: Vec<Option<&str>> = vec![];
+        #(#field_vector_decls)*
 
-            fn into_series(rows: impl Iterator<Item = Self>) -> Vec<::polars::series::Series> {
-                #series_impl
+        #rows_loop {
+            match item {
+                #(#arms)*
+                #none_arm
             }
         }
+
+        let tags = <::polars::series::Series as ::polars::prelude::NamedFrom<_, _>>::new(
+            #tag,
+            tags.as_slice(),
+        )
+        #cast;
+
+        Ok(::std::iter::once(tags)
+            #(.chain(#series_decls))*
+            .collect::<Vec<_>>())
     }
 }
 
-fn vec_to_series(name: &str, inner: impl ToTokens, dtype: &DataType) -> TokenStream2 {
+/// Returns Rust code which builds a Series called `name` out of `inner`, a
+/// vector of values of the Rust type `rtype`, if it is known. If the values
+/// fail to convert, the code returns early with an error that refers to the
+/// index of the value, or to `row` if `in_list` is true, in which case the
+/// values are the items of the list in that row.
+fn vec_to_series(
+    name: &str,
+    inner: impl ToTokens,
+    dtype: &DataType,
+    rtype: Option<&syn::Type>,
+    optional: bool,
+    in_list: bool,
+) -> TokenStream2 {
     match dtype {
         DataType::Boolean
         | DataType::UInt8
@@ -122,11 +933,7 @@ fn vec_to_series(name: &str, inner: impl ToTokens, dtype: &DataType) -> TokenStr
         | DataType::Float32
         | DataType::Float64
         | DataType::Utf8
-        | DataType::Date
-        | DataType::Datetime(_, _)
-        | DataType::Duration(_)
-        | DataType::Binary
-        | DataType::Time => {
+        | DataType::Binary => {
             // scalar data types are simple
             quote_spanned! {inner.span()=>
                 {
@@ -138,23 +945,181 @@ fn vec_to_series(name: &str, inner: impl ToTokens, dtype: &DataType) -> TokenStr
                 }
             }
         }
+        DataType::Datetime(_, _) | DataType::Date | DataType::Time | DataType::Duration(_) => {
+            // temporal values are converted into their physical
+            // representation and then cast. datetimes are stored as
+            // timestamps, whichever time zone they are in
+            let (physical, convert) = match dtype {
+                DataType::Datetime(tu, _) => {
+                    let tu = time_unit_to_expr(*tu);
+                    (
+                        quote! { Int64Chunked },
+                        quote! { ::polars_derive::helpers::ToTimestamp::to_timestamp(d, #tu) },
+                    )
+                }
+                DataType::Date => (
+                    quote! { Int32Chunked },
+                    quote! { Ok(::polars_derive::helpers::ToDate::to_date(d)) },
+                ),
+                DataType::Time => (
+                    quote! { Int64Chunked },
+                    quote! { Ok(::polars_derive::helpers::ToTime::to_time(d)) },
+                ),
+                DataType::Duration(tu) => {
+                    let tu = time_unit_to_expr(*tu);
+                    (
+                        quote! { Int64Chunked },
+                        quote! { ::polars_derive::helpers::ToDuration::to_duration(d, #tu) },
+                    )
+                }
+                _ => unreachable!(),
+            };
+            let dtype = dtype_to_expr(dtype);
+            let row = if in_list {
+                quote! { _ }
+            } else {
+                quote! { row }
+            };
+
+            // a value which doesn't fit in the physical representation (ex.:
+            // a datetime or a duration in nanoseconds) fails to convert
+            quote_spanned! {inner.span()=>
+                {
+                    let v = #inner;
+                    let values = v
+                        .iter()
+                        .enumerate()
+                        .map(|(#row, d)| {
+                            #convert.map_err(|err: ::polars_derive::helpers::Error| {
+                                ::polars_derive::__private::conversion_error(#name, row, err)
+                            })
+                        })
+                        .collect::<::polars::error::PolarsResult<Vec<_>>>()?;
+                    let values: ::polars::prelude::#physical =
+                        ::polars::prelude::NamedFrom::new(#name, values);
+                    ::polars::prelude::IntoSeries::into_series(values).cast(&#dtype)?
+                }
+            }
+        }
         DataType::List(inner_dtype) => {
             // for list types, recurse
             let local = format_ident!("i");
-            let inner_converter = vec_to_series(name, local.clone(), &*inner_dtype);
+            let (item_rtype, item_optional) = match rtype.and_then(list_item_type) {
+                Some((item_rtype, item_optional)) => (Some(item_rtype), item_optional),
+                None => (None, false),
+            };
+            let inner_converter = vec_to_series(
+                name,
+                local.clone(),
+                inner_dtype,
+                item_rtype,
+                item_optional,
+                true,
+            );
+
+            // the lists might be borrowed, so the Option is matched instead of
+            // mapped, which works either way
+            let inner_converter = if optional {
+                quote! {
+                    match #local {
+                        Some(#local) => Some(#inner_converter),
+                        None => None,
+                    }
+                }
+            } else {
+                inner_converter
+            };
+
+            // Polars infers the data type of a list from its first item, so it
+            // can't build a list column if there are no items
+            let empty = if optional {
+                quote! { v.iter().all(|#local| #local.is_none()) }
+            } else {
+                quote! { v.is_empty() }
+            };
+            let dtype = dtype_to_expr(dtype);
+            let row = if in_list {
+                quote! { _ }
+            } else {
+                quote! { row }
+            };
 
             quote_spanned! {inner.span()=>
                 {
-                    let v = #inner.into_iter().map(|#local| #inner_converter).collect::<Vec<::polars::series::Series>>();
-                    <::polars::series::Series as ::polars::prelude::NamedFrom<_, _>>::new(
-                      #name,
-                      v.as_slice()
+                    let v = #inner
+                        .into_iter()
+                        .enumerate()
+                        .map(|(#row, #local)| -> ::polars::error::PolarsResult<_> {
+                            Ok(#inner_converter)
+                        })
+                        .collect::<::polars::error::PolarsResult<Vec<_>>>()?;
+                    if #empty {
+                        ::polars::series::Series::full_null(#name, v.len(), &#dtype)
+                    } else {
+                        <::polars::series::Series as ::polars::prelude::NamedFrom<_, _>>::new(
+                          #name,
+                          v.as_slice()
+                        )
+                    }
+                }
+            }
+        }
+        #[cfg(feature = "dtype-struct")]
+        DataType::Struct(fields) => {
+            // structs are built from tuples, so we split the tuples up into one
+            // vector per element and then recurse
+            let elem_names: Vec<_> = (0..fields.len())
+                .map(|idx| format_ident!("e{idx}"))
+                .collect();
+            let vector_names: Vec<_> = (0..fields.len())
+                .map(|idx| format_ident!("f{idx}"))
+                .collect();
+
+            let vector_fillers = if optional {
+                quote! {
+                    match t {
+                        Some((#(#elem_names),*,)) => {
+                            #(#vector_names.push(Some(#elem_names));)*
+                        }
+                        None => {
+                            #(#vector_names.push(None);)*
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    let (#(#elem_names),*,) = t;
+                    #(#vector_names.push(#elem_names);)*
+                }
+            };
+
+            let series_decls = fields.iter().zip(&vector_names).map(|(field, var_name)| {
+                vec_to_series(
+                    field.name(),
+                    var_name,
+                    field.data_type(),
+                    None,
+                    optional,
+                    in_list,
+                )
+            });
+
+            quote_spanned! {inner.span()=>
+                {
+                    let v = #inner;
+                    #(let mut #vector_names = Vec::with_capacity(v.len());)*
+
+                    for t in v {
+                        #vector_fillers
+                    }
+
+                    ::polars::prelude::IntoSeries::into_series(
+                        ::polars::prelude::StructChunked::new(#name, &[#(#series_decls),*])
+                            .expect("fields of a struct should have the same length"),
                     )
                 }
             }
         }
-        DataType::Null => todo!(),
-        DataType::Struct(_) => todo!(),
-        DataType::Unknown => todo!(),
+        other => unsupported_dtype(other),
     }
 }
@@ -6,6 +6,7 @@ extern crate proc_macro;
 mod common;
 mod from_df;
 mod into_df;
+mod rename;
 
 #[proc_macro_derive(IntoDataFrame, attributes(df))]
 pub fn derive_into_df(input: TokenStream) -> TokenStream {
@@ -18,3 +19,9 @@ pub fn derive_from_df(input: TokenStream) -> TokenStream {
     let input = TokenStream2::from(input);
     proc_macro::TokenStream::from(from_df::derive(input))
 }
+
+#[proc_macro_derive(FromDataFrameRef, attributes(df))]
+pub fn derive_from_df_ref(input: TokenStream) -> TokenStream {
+    let input = TokenStream2::from(input);
+    proc_macro::TokenStream::from(from_df::derive_ref(input))
+}
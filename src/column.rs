@@ -0,0 +1,959 @@
+#[cfg(feature = "dtype-time")]
+use polars::export::chrono::NaiveTime;
+use polars::export::chrono::{
+    DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+use polars::prelude::*;
+
+use crate::__private::{conversion_error, dtype_mismatch};
+use crate::helpers::{ToDuration, ToTimestamp};
+use crate::Error;
+
+/// This trait allows a type to be stored in a single column of a
+/// [`DataFrame`].
+///
+/// With the `dtype-struct` feature, it is implemented by
+/// `#[derive(IntoDataFrame)]`, so that a structure can be used as a field of
+/// another structure, in which case it is stored as a `Struct` column. Since
+/// Polars structs do not track nulls of their own, a `None` is stored as a
+/// null in each of the struct's fields.
+///
+/// It is also implemented for the types which the derive macros recognize by
+/// name (the primitive types, `String`, the chrono types, `Option<T>`,
+/// `Vec<T>` and tuples, as well as the types of `std::time` and of the `time`
+/// crate with their features), so that they can be used as the type
+/// parameters of generic structures. Fields with types that are not
+/// recognized by name are stored through this trait, so implementing it for a
+/// type allows it to be used as a field. `DateTime<Tz>` is implemented for any
+/// time zone, while [`FromColumn`] is only implemented for `DateTime<Utc>`,
+/// `DateTime<FixedOffset>` and, with the `chrono-tz` feature,
+/// `DateTime<chrono_tz::Tz>`.
+pub trait IntoColumn: Sized {
+    /// Whether a `Vec` of this type is stored as `Binary` instead of as a list.
+    /// Like in the derive macros, this is only the case for `u8`.
+    const BINARY_ITEM: bool = false;
+
+    /// The data type of the column that this type is stored in.
+    fn dtype() -> DataType;
+
+    /// Builds a column called `name` which contains `values`.
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series;
+
+    /// Same as [`IntoColumn::into_column`], but only borrows the values.
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series;
+
+    /// Same as [`IntoColumn::into_column`], but returns an error instead of
+    /// panicking if a value can't be converted.
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Ok(Self::into_column(name, values))
+    }
+
+    /// Same as [`IntoColumn::to_column`], but returns an error instead of
+    /// panicking if a value can't be converted.
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        Ok(Self::to_column(name, values))
+    }
+}
+
+/// This trait allows a type to be read out of a single column of a
+/// [`DataFrame`].
+///
+/// With the `dtype-struct` feature, it is implemented by
+/// `#[derive(FromDataFrame)]`, so that a structure can be used as a field of
+/// another structure, in which case it is read from a `Struct` column. A row
+/// where each of the struct's fields is null is read as `None`, unless the
+/// field isn't an `Option` (see [`FromColumn::from_required_column`]). Like
+/// [`IntoColumn`], it is also implemented for the types which the derive
+/// macros recognize by name, and can be implemented for other types.
+pub trait FromColumn: Sized {
+    /// Reads each of the values in `series`, where nulls are read as `None`.
+    /// Returns an error if the column can't be read at all (ex.: it has
+    /// another data type), while a value which can't be read is an error for
+    /// its own row, so that the other rows can still be read. The errors refer
+    /// to the column by the name of `series`.
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error>;
+
+    /// Same as [`FromColumn::from_column`], but for values which can't be
+    /// missing, such as fields and list items which aren't `Option`s. Polars
+    /// doesn't keep track of which rows of a `Struct` column are null, so a
+    /// structure whose fields are all `None` is stored in the same way as a
+    /// missing one. This reads such a row as the structure instead of `None`.
+    fn from_required_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        Self::from_column(series)
+    }
+}
+
+macro_rules! impl_column_for_primitive {
+    ($ty:ty, $dtype:expr, $method:ident $(, binary_item = $binary_item:literal)?) => {
+        impl IntoColumn for $ty {
+            $(const BINARY_ITEM: bool = $binary_item;)?
+
+            fn dtype() -> DataType {
+                $dtype
+            }
+
+            fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+                Series::new(name, values)
+            }
+
+            fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+                let values: Vec<_> = values.into_iter().map(|value| value.copied()).collect();
+                Series::new(name, values)
+            }
+        }
+
+        impl FromColumn for $ty {
+            fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+                let values = series
+                    .$method()
+                    .map_err(|_| dtype_mismatch(series.name(), $dtype, series))?;
+
+                Ok(values.into_iter().map(Ok).collect())
+            }
+        }
+    };
+}
+
+impl_column_for_primitive!(bool, DataType::Boolean, bool);
+#[cfg(feature = "dtype-u8")]
+impl_column_for_primitive!(u8, DataType::UInt8, u8, binary_item = true);
+#[cfg(feature = "dtype-u16")]
+impl_column_for_primitive!(u16, DataType::UInt16, u16);
+impl_column_for_primitive!(u32, DataType::UInt32, u32);
+impl_column_for_primitive!(u64, DataType::UInt64, u64);
+#[cfg(feature = "dtype-i8")]
+impl_column_for_primitive!(i8, DataType::Int8, i8);
+#[cfg(feature = "dtype-i16")]
+impl_column_for_primitive!(i16, DataType::Int16, i16);
+impl_column_for_primitive!(i32, DataType::Int32, i32);
+impl_column_for_primitive!(i64, DataType::Int64, i64);
+impl_column_for_primitive!(f32, DataType::Float32, f32);
+impl_column_for_primitive!(f64, DataType::Float64, f64);
+
+impl IntoColumn for String {
+    fn dtype() -> DataType {
+        DataType::Utf8
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Series::new(name, values)
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        let values: Vec<_> = values
+            .into_iter()
+            .map(|value| value.map(String::as_str))
+            .collect();
+        Series::new(name, values)
+    }
+}
+
+impl FromColumn for String {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let values = series
+            .utf8()
+            .map_err(|_| dtype_mismatch(series.name(), DataType::Utf8, series))?;
+
+        Ok(values
+            .into_iter()
+            .map(|value| Ok(value.map(str::to_owned)))
+            .collect())
+    }
+}
+
+impl IntoColumn for NaiveDateTime {
+    fn dtype() -> DataType {
+        DataType::Datetime(TimeUnit::Milliseconds, None)
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_timestamp(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+impl FromColumn for NaiveDateTime {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        // datetimes are read in the time unit of the column
+        let datetimes = series
+            .datetime()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+        let unit = datetimes.time_unit();
+
+        Ok(convert_values(series, datetimes.into_iter(), |value| {
+            crate::helpers::deserialize_naive_datetime(value, unit)
+        }))
+    }
+}
+
+impl<Tz: TimeZone> IntoColumn for DateTime<Tz> {
+    fn dtype() -> DataType {
+        DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".to_owned()))
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_timestamp(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+impl FromColumn for DateTime<Utc> {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        convert_datetimes(series, |value, unit, _| {
+            crate::helpers::deserialize_datetime_utc(value, unit)
+        })
+    }
+}
+
+impl FromColumn for DateTime<FixedOffset> {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        convert_datetimes(series, crate::helpers::deserialize_datetime_fixed)
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl FromColumn for DateTime<chrono_tz::Tz> {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        convert_datetimes(series, crate::helpers::deserialize_datetime_tz)
+    }
+}
+
+impl IntoColumn for NaiveDate {
+    fn dtype() -> DataType {
+        DataType::Date
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        let days: Vec<_> = values.iter().map(crate::helpers::ToDate::to_date).collect();
+
+        Int32Chunked::new(name, days)
+            .into_series()
+            .cast(&Self::dtype())
+    }
+}
+
+impl FromColumn for NaiveDate {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let values = series
+            .cast(&Self::dtype())
+            .map_err(|source| column_error(series, source))?;
+        let values = values
+            .date()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+
+        Ok(convert_values(
+            series,
+            values.into_iter(),
+            crate::helpers::deserialize_date,
+        ))
+    }
+}
+
+#[cfg(feature = "dtype-time")]
+impl IntoColumn for NaiveTime {
+    fn dtype() -> DataType {
+        DataType::Time
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        let nanos: Vec<_> = values.iter().map(crate::helpers::ToTime::to_time).collect();
+
+        Int64Chunked::new(name, nanos)
+            .into_series()
+            .cast(&Self::dtype())
+    }
+}
+
+#[cfg(feature = "dtype-time")]
+impl FromColumn for NaiveTime {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let values = series
+            .time()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+
+        Ok(convert_values(
+            series,
+            values.into_iter(),
+            crate::helpers::deserialize_time,
+        ))
+    }
+}
+
+impl IntoColumn for Duration {
+    fn dtype() -> DataType {
+        DataType::Duration(TimeUnit::Milliseconds)
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_duration(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+impl FromColumn for Duration {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        // durations are read in the time unit of the column
+        let durations = series
+            .duration()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+        let unit = durations.time_unit();
+
+        Ok(convert_values(series, durations.into_iter(), |value| {
+            crate::helpers::deserialize_duration(value, unit)
+        }))
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl IntoColumn for std::time::SystemTime {
+    fn dtype() -> DataType {
+        DataType::Datetime(TimeUnit::Milliseconds, None)
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_timestamp(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl FromColumn for std::time::SystemTime {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        // datetimes are read in the time unit of the column
+        let datetimes = series
+            .datetime()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+        let unit = datetimes.time_unit();
+
+        Ok(convert_values(series, datetimes.into_iter(), |value| {
+            crate::helpers::deserialize_system_time(value, unit)
+        }))
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl IntoColumn for std::time::Duration {
+    fn dtype() -> DataType {
+        DataType::Duration(TimeUnit::Milliseconds)
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_duration(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+#[cfg(feature = "std-time")]
+impl FromColumn for std::time::Duration {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let durations = series
+            .duration()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+        let unit = durations.time_unit();
+
+        Ok(convert_values(series, durations.into_iter(), |value| {
+            crate::helpers::deserialize_std_duration(value, unit)
+        }))
+    }
+}
+
+#[cfg(feature = "time")]
+impl IntoColumn for time::OffsetDateTime {
+    fn dtype() -> DataType {
+        DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".to_owned()))
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_timestamp(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromColumn for time::OffsetDateTime {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        convert_datetimes(series, crate::helpers::deserialize_offset_datetime)
+    }
+}
+
+#[cfg(feature = "time")]
+impl IntoColumn for time::Date {
+    fn dtype() -> DataType {
+        DataType::Date
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        let days: Vec<_> = values.iter().map(crate::helpers::ToDate::to_date).collect();
+
+        Int32Chunked::new(name, days)
+            .into_series()
+            .cast(&Self::dtype())
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromColumn for time::Date {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let values = series
+            .cast(&Self::dtype())
+            .map_err(|source| column_error(series, source))?;
+        let values = values
+            .date()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+
+        Ok(convert_values(
+            series,
+            values.into_iter(),
+            crate::helpers::FromDate::from_date,
+        ))
+    }
+}
+
+#[cfg(feature = "time")]
+impl IntoColumn for time::Duration {
+    fn dtype() -> DataType {
+        DataType::Duration(TimeUnit::Milliseconds)
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        Self::try_to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        physical_column(
+            name,
+            Self::dtype(),
+            values
+                .iter()
+                .map(|value| value.to_duration(TimeUnit::Milliseconds)),
+        )
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromColumn for time::Duration {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let durations = series
+            .duration()
+            .map_err(|_| dtype_mismatch(series.name(), Self::dtype(), series))?;
+        let unit = durations.time_unit();
+
+        Ok(convert_values(series, durations.into_iter(), |value| {
+            crate::helpers::FromDuration::from_duration(value, unit)
+        }))
+    }
+}
+
+/// Converts each of `values`, the values of `series`, using `convert`. A
+/// value which can't be converted is an error for its own row.
+fn convert_values<T, U, E>(
+    series: &Series,
+    values: impl Iterator<Item = Option<T>>,
+    convert: impl Fn(T) -> Result<U, E>,
+) -> Vec<Result<Option<U>, Error>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    values
+        .enumerate()
+        .map(|(row, value)| {
+            value
+                .map(|value| {
+                    convert(value).map_err(|err| Error::InvalidValue {
+                        column: series.name().to_owned(),
+                        row,
+                        source: Box::new(err),
+                    })
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+/// Builds a column of type `dtype` called `name` out of `values`, the
+/// physical values of its items, such as timestamps. A value which failed to
+/// convert (ex.: one that doesn't fit in 64 bits) is an error for its row.
+fn physical_column(
+    name: &str,
+    dtype: DataType,
+    values: impl Iterator<Item = Result<Option<i64>, crate::helpers::Error>>,
+) -> PolarsResult<Series> {
+    let values = values
+        .enumerate()
+        .map(|(row, value)| value.map_err(|err| conversion_error(name, row, err)))
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    Int64Chunked::new(name, values).into_series().cast(&dtype)
+}
+
+/// Converts each of the timestamps in `series` using `convert`, which is
+/// given the time unit and the time zone of the column. A column without a
+/// time zone is read as `UTC`.
+fn convert_datetimes<T>(
+    series: &Series,
+    convert: impl Fn(i64, TimeUnit, &str) -> Result<T, crate::helpers::Error>,
+) -> Result<Vec<Result<Option<T>, Error>>, Error> {
+    let datetimes = series.datetime().map_err(|_| {
+        dtype_mismatch(
+            series.name(),
+            DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".to_owned())),
+            series,
+        )
+    })?;
+    let unit = datetimes.time_unit();
+    let tz = datetimes.time_zone().as_deref().unwrap_or("UTC");
+
+    Ok(convert_values(series, datetimes.into_iter(), |value| {
+        convert(value, unit, tz)
+    }))
+}
+
+/// Returns an error for `series` which can't be read at all.
+fn column_error(series: &Series, source: PolarsError) -> Error {
+    Error::Column {
+        column: series.name().to_owned(),
+        source,
+    }
+}
+
+impl<T: IntoColumn> IntoColumn for Option<T> {
+    fn dtype() -> DataType {
+        T::dtype()
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        T::into_column(name, values.into_iter().map(Option::flatten).collect())
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        T::to_column(
+            name,
+            values
+                .into_iter()
+                .map(|value| value.and_then(Option::as_ref))
+                .collect(),
+        )
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        T::try_into_column(name, values.into_iter().map(Option::flatten).collect())
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        T::try_to_column(
+            name,
+            values
+                .into_iter()
+                .map(|value| value.and_then(Option::as_ref))
+                .collect(),
+        )
+    }
+}
+
+impl<T: FromColumn> FromColumn for Option<T> {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        // a null is a valid value, so every row is present
+        Ok(T::from_column(series)?
+            .into_iter()
+            .map(|value| value.map(Some))
+            .collect())
+    }
+}
+
+/// Lists are built out of one Series per row. Like in the derive macros, a
+/// `Vec<u8>` is stored as `Binary` instead, while a `Vec<Option<u8>>` is stored
+/// as a list of `UInt8` (see [`IntoColumn::BINARY_ITEM`]).
+impl<T: IntoColumn> IntoColumn for Vec<T> {
+    fn dtype() -> DataType {
+        if T::BINARY_ITEM {
+            DataType::Binary
+        } else {
+            DataType::List(Box::new(T::dtype()))
+        }
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+        let rows = values
+            .into_iter()
+            .map(|row| {
+                row.map(|row| T::try_into_column("", row.into_iter().map(Some).collect()))
+                    .transpose()
+            })
+            .collect::<PolarsResult<_>>()?;
+
+        list_column::<T>(name, rows)
+    }
+
+    fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+        let rows = values
+            .into_iter()
+            .map(|row| {
+                row.map(|row| T::try_to_column("", row.iter().map(Some).collect()))
+                    .transpose()
+            })
+            .collect::<PolarsResult<_>>()?;
+
+        list_column::<T>(name, rows)
+    }
+}
+
+/// Builds a list column out of the Series for each of its rows.
+fn list_column<T: IntoColumn>(name: &str, rows: Vec<Option<Series>>) -> PolarsResult<Series> {
+    let dtype = Vec::<T>::dtype();
+
+    // Polars infers the data type of a list from its first item, so it can't
+    // build a list column if there are no items
+    if rows.iter().all(Option::is_none) {
+        return Ok(Series::full_null(name, rows.len(), &dtype));
+    }
+
+    if dtype == DataType::Binary {
+        let rows = rows
+            .iter()
+            .map(|row| {
+                row.as_ref()
+                    .map(|row| {
+                        // a byte of Binary can't be null
+                        if row.null_count() > 0 {
+                            return Err(PolarsError::ComputeError(
+                                format!("the bytes of column {} cannot be null", name).into(),
+                            ));
+                        }
+
+                        Ok(row.u8()?.into_no_null_iter().collect::<Vec<_>>())
+                    })
+                    .transpose()
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        return Ok(Series::new(name, rows));
+    }
+
+    Ok(Series::new(name, rows))
+}
+
+impl<T: FromColumn> FromColumn for Vec<T> {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        // lists of bytes are stored as Binary, see IntoColumn::dtype
+        #[cfg(feature = "dtype-u8")]
+        if series.dtype() == &DataType::Binary {
+            let rows = series
+                .binary()
+                .map_err(|source| column_error(series, source))?;
+
+            return rows
+                .into_iter()
+                .enumerate()
+                .map(|(row, bytes)| {
+                    bytes
+                        .map(|bytes| {
+                            let items = UInt8Chunked::from_slice("", bytes).into_series();
+                            read_list(series, row, items)
+                        })
+                        .transpose()
+                        .map(Option::transpose)
+                })
+                .collect();
+        }
+
+        let rows = series
+            .list()
+            .map_err(|source| column_error(series, source))?;
+
+        rows.into_iter()
+            .enumerate()
+            .map(|(row, items)| {
+                items
+                    .map(|items| read_list(series, row, items))
+                    .transpose()
+                    .map(Option::transpose)
+            })
+            .collect()
+    }
+}
+
+/// Reads `items`, the items of the list in `row` of `series`. Returns an
+/// error if the items can't be read at all, or otherwise the list or the error
+/// for the row. Errors for the items are reported for the row of the list.
+fn read_list<T: FromColumn>(
+    series: &Series,
+    row: usize,
+    mut items: Series,
+) -> Result<Result<Vec<T>, Error>, Error> {
+    let name = format!("{}.<item>", series.name());
+    items.rename(&name);
+
+    Ok(T::from_required_column(&items)?
+        .into_iter()
+        .map(|item| match item {
+            Ok(Some(item)) => Ok(item),
+            Ok(None) => Err(Error::MissingValue {
+                column: name.clone(),
+                row,
+            }),
+            Err(err) => Err(err.with_row(row)),
+        })
+        .collect())
+}
+
+/// Tuples are stored as `Struct` columns with fields named `0`, `1`, and so on.
+macro_rules! impl_column_for_tuple {
+    ($($ty:ident $values:ident $iter:ident $idx:tt),+) => {
+        #[cfg(feature = "dtype-struct")]
+        impl<$($ty: IntoColumn),+> IntoColumn for ($($ty,)+) {
+            fn dtype() -> DataType {
+                DataType::Struct(vec![$(Field::new(stringify!($idx), $ty::dtype())),+])
+            }
+
+            fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+                Self::try_into_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+            }
+
+            fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+                Self::try_to_column(name, values).unwrap_or_else(|err| panic!("{}", err))
+            }
+
+            fn try_into_column(name: &str, values: Vec<Option<Self>>) -> PolarsResult<Series> {
+                $(let mut $values = Vec::with_capacity(values.len());)+
+
+                for value in values {
+                    match value {
+                        Some(value) => {
+                            $($values.push(Some(value.$idx));)+
+                        }
+                        None => {
+                            $($values.push(None);)+
+                        }
+                    }
+                }
+
+                let fields = [$($ty::try_into_column(stringify!($idx), $values)?),+];
+                Ok(StructChunked::new(name, &fields)?.into_series())
+            }
+
+            fn try_to_column(name: &str, values: Vec<Option<&Self>>) -> PolarsResult<Series> {
+                $(let mut $values = Vec::with_capacity(values.len());)+
+
+                for value in values {
+                    match value {
+                        Some(value) => {
+                            $($values.push(Some(&value.$idx));)+
+                        }
+                        None => {
+                            $($values.push(None);)+
+                        }
+                    }
+                }
+
+                let fields = [$($ty::try_to_column(stringify!($idx), $values)?),+];
+                Ok(StructChunked::new(name, &fields)?.into_series())
+            }
+        }
+
+        #[cfg(feature = "dtype-struct")]
+        impl<$($ty: FromColumn),+> FromColumn for ($($ty,)+) {
+            fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+                let rows = Self::from_required_column(series)?;
+                Ok(crate::__private::struct_nulls(series, rows))
+            }
+
+            fn from_required_column(
+                series: &Series,
+            ) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+                let st = series.struct_().map_err(|source| column_error(series, source))?;
+                let prefix = format!("{}.", series.name());
+                $(
+                    let $iter = st
+                        .field_by_name(stringify!($idx))
+                        .map_err(|_| Error::MissingColumn {
+                            column: format!("{}{}", prefix, stringify!($idx)),
+                        })?;
+                    let mut $iter = $ty::from_required_column(&$iter)
+                        .map_err(|err| err.with_prefix(&prefix))?
+                        .into_iter();
+                )+
+
+                let mut out = vec![];
+
+                while let ($(Some($values),)+) = ($($iter.next(),)+) {
+                    let row = out.len();
+                    let value = (|| -> Result<Option<Self>, Error> {
+                        $(let $values = $values.map_err(|err| err.with_prefix(&prefix))?;)+
+
+                        Ok(Some(($(
+                            $values.ok_or_else(|| Error::MissingValue {
+                                column: format!("{}{}", prefix, stringify!($idx)),
+                                row,
+                            })?,
+                        )+)))
+                    })();
+
+                    out.push(value);
+                }
+
+                Ok(out)
+            }
+        }
+    };
+}
+
+impl_column_for_tuple!(A a ia 0);
+impl_column_for_tuple!(A a ia 0, B b ib 1);
+impl_column_for_tuple!(A a ia 0, B b ib 1, C c ic 2);
+impl_column_for_tuple!(A a ia 0, B b ib 1, C c ic 2, D d id 3);
+impl_column_for_tuple!(A a ia 0, B b ib 1, C c ic 2, D d id 3, E e ie 4);
+impl_column_for_tuple!(A a ia 0, B b ib 1, C c ic 2, D d id 3, E e ie 4, F f if_ 5);
@@ -0,0 +1,117 @@
+use polars::error::ErrString;
+use polars::prelude::*;
+use thiserror::Error;
+
+/// An error which occurred while reading rows out of a [`DataFrame`]. The
+/// errors which are specific to a single value record the index of the row it
+/// was in.
+///
+/// This can be converted into a [`PolarsError`], so `?` still works in
+/// functions which return [`PolarsResult`].
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The column for a field is missing.
+    #[error("missing column {column}")]
+    MissingColumn { column: String },
+
+    /// The column for a field does not have the data type that the field is
+    /// read from.
+    #[error("column {column} has data type {actual}, but {expected} was expected")]
+    DtypeMismatch {
+        column: String,
+        expected: DataType,
+        actual: DataType,
+    },
+
+    /// A value is null, but the field is not an `Option`.
+    #[error("missing value for field {column} in row {row}")]
+    MissingValue { column: String, row: usize },
+
+    /// A value could not be converted into the type of the field, either by
+    /// `try_from` or by `deserialize_with`.
+    #[error("invalid value for field {column} in row {row}: {source}")]
+    InvalidValue {
+        column: String,
+        row: usize,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The tag column of an enum holds a name which is not one of its
+    /// variants.
+    #[error("unknown variant {variant} for field {column} in row {row}")]
+    UnknownVariant {
+        column: String,
+        row: usize,
+        variant: String,
+    },
+
+    /// A column could not be read at all, ex.: a nested column which is not a
+    /// `Struct`. The errors for single values are reported as the other
+    /// variants, which record the row.
+    #[error("failed to read column {column}: {source}")]
+    Column { column: String, source: PolarsError },
+
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+}
+
+impl Error {
+    /// Returns this error with `prefix` added to the name of its column. This
+    /// is used for errors in the fields of nested structures.
+    pub(crate) fn with_prefix(mut self, prefix: &str) -> Self {
+        match &mut self {
+            Error::MissingColumn { column }
+            | Error::DtypeMismatch { column, .. }
+            | Error::MissingValue { column, .. }
+            | Error::InvalidValue { column, .. }
+            | Error::UnknownVariant { column, .. }
+            | Error::Column { column, .. } => column.insert_str(0, prefix),
+            Error::Polars(_) => {}
+        }
+
+        self
+    }
+
+    /// Returns this error with its row replaced by `row`. This is used for
+    /// errors in the items of lists, which are reported for the row that the
+    /// list is in.
+    pub(crate) fn with_row(mut self, row: usize) -> Self {
+        match &mut self {
+            Error::MissingValue { row: r, .. }
+            | Error::InvalidValue { row: r, .. }
+            | Error::UnknownVariant { row: r, .. } => *r = row,
+            _ => {}
+        }
+
+        self
+    }
+}
+
+/// An error which occurred while reading a single row, returned by
+/// [`FromDataFrame::from_df_lenient`](crate::FromDataFrame::from_df_lenient).
+#[derive(Error, Debug)]
+#[error("failed to read row {row}")]
+pub struct RowError {
+    /// The index of the row in the `DataFrame`.
+    pub row: usize,
+    #[source]
+    pub error: Error,
+}
+
+impl From<Error> for PolarsError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Polars(err) => err,
+            Error::MissingColumn { .. } => {
+                PolarsError::ColumnNotFound(ErrString::from(err.to_string()))
+            }
+            Error::MissingValue { .. } => PolarsError::NoData(ErrString::from(err.to_string())),
+            Error::Column { .. } => PolarsError::ComputeError(ErrString::from(err.to_string())),
+            Error::DtypeMismatch { .. }
+            | Error::InvalidValue { .. }
+            | Error::UnknownVariant { .. } => {
+                PolarsError::SchemaMismatch(ErrString::from(err.to_string()))
+            }
+        }
+    }
+}
@@ -2,10 +2,126 @@
 //!
 //! Contains derive macros intended to be used with `polars` so that serializing
 //! and deserializing Rust structures into `DataFrame`s is less manual.
+//!
+//! The attributes which the derive macros take are listed in the docs of
+//! [`IntoDataFrame`] and [`FromDataFrame`].
+//!
+//! # Types
+//! If the data type is not specified explicitly using `#[df(dtype)]`, it is
+//! inferred from the type of the field. The primitive types, `String`,
+//! `NaiveDateTime`, `DateTime<Tz>`, `NaiveDate`, `NaiveTime`, `Duration`,
+//! `Vec<T>` and tuples are recognized by name, when they are named directly or
+//! through the module they come from (like `chrono::Duration`). Fields can be
+//! `Option<T>`, and so can the items of lists (ex.: `Vec<Option<T>>`), which
+//! are stored as nulls.
+//!
+//! Any other type is stored through its [`IntoColumn`] impl and read through
+//! its [`FromColumn`] impl, so a type from another crate (or a type alias) can
+//! be used as a field by implementing these traits for it. A type of your own
+//! which has the same name as one of the recognized types can be named through
+//! its module instead, like `self::Duration`.
+//!
+//! Fields whose type is another structure that derives the macros are stored
+//! in a single `Struct` column. Tuple fields are also stored as `Struct`
+//! columns, with fields named `0`, `1`, and so on. Both need the
+//! `dtype-struct` feature, which is enabled by default.
+//!
+//! # Dates and times
+//! `NaiveDateTime` is stored in a `Datetime` column without a time zone, while
+//! `DateTime<Tz>` is stored in a `Datetime` column whose time zone is `UTC`, or
+//! the one set using `#[df(timezone)]`. The timestamps are the same whichever
+//! time zone the values are in. When they are read, the values are put in the
+//! time zone of the column (or `UTC` if it has none), which has to fit the type
+//! of the field: `Utc` takes any time zone, `FixedOffset` takes `UTC` or an
+//! offset like `+02:00`, and other types take the time zones that they can be
+//! parsed out of, like the names taken by `chrono_tz::Tz`.
+//!
+//! The timestamps are written in milliseconds unless another unit is set using
+//! `#[df(time_unit)]`, and are read in the time unit of the column. Datetimes
+//! which don't fit in 64 bits, like ones outside of the years 1677 to 2262 in
+//! nanoseconds, fail to convert.
+//!
+//! `NaiveDate` is stored in a `Date` column, `NaiveTime` in a `Time` column
+//! (which needs the `dtype-time` feature) and chrono's `Duration` in a
+//! `Duration` column, whose time unit works in the same way as for datetimes.
+//!
+//! With the `std-time` feature, `std::time::SystemTime` is stored in a
+//! `Datetime` column without a time zone and `std::time::Duration` in a
+//! `Duration` column. Negative durations can't be read into a
+//! `std::time::Duration`.
+//!
+//! With the `time` feature, the `time` crate's `OffsetDateTime` is stored like
+//! a `DateTime<FixedOffset>`, `Date` like a `NaiveDate` and `Duration` like
+//! chrono's `Duration`.
+//!
+//! The types of `std::time` and of the `time` crate are only recognized when
+//! they are named through their module (like `std::time::SystemTime` or
+//! `time::Date`), which is an error if their feature is not enabled. When they
+//! are named on their own, they are stored through [`IntoColumn`] like any
+//! other type, so `#[df(timezone)]` and `#[df(time_unit)]` can't be used.
+//! Since a module of your own might also be called `time`, a path like
+//! `time::Date` is only taken for the `time` crate if the `time` feature is
+//! enabled, while `::time::Date` always is. With the feature enabled, types in
+//! a module of your own called `time` can be named like `self::time::Date`.
+//!
+//! With the `chrono-tz` feature, `DateTime<chrono_tz::Tz>` can also be read
+//! through [`FromColumn`], so it can be used in generic structures.
+//!
+//! # Generics
+//! Generic structures are supported. Fields whose types use the type
+//! parameters are stored through [`IntoColumn`] and read through
+//! [`FromColumn`], and the derived impls require each of these types to
+//! implement them. These bounds can be replaced using `#[df(bound =
+//! "<bounds>")]` on the structure (ex.: `#[df(bound = "T: IntoColumn +
+//! Clone")]`).
+//!
+//! # Borrowing
+//! [`IntoDataFrame::to_df`] and [`IntoDataFrame::to_series`] build the
+//! `DataFrame` out of borrowed rows. Strings, binary data and lists are copied
+//! directly out of the rows, without cloning the fields first. Fields which are
+//! converted by value using `into`, `try_into`, `serialize_with` or
+//! `try_serialize_with` still need to be owned, so they are cloned, and their
+//! types need to implement `Clone`. For generic fields, this bound is added to
+//! the derived impl unless the bounds are given using `bound`.
+//!
+//! # Enums
+//! Enums whose variants have no fields implement [`IntoColumn`] and
+//! [`FromColumn`], and are stored as a `Utf8` or `Categorical` column
+//! containing the names of the variants. Either kind of column can be read.
+//!
+//! Enums whose variants have named fields implement [`IntoDataFrame`] and
+//! [`FromDataFrame`]. Each row is stored as the name of its variant in a tag
+//! column, followed by the union of the columns of all of the variants. The
+//! columns which belong to other variants are null. Variants can share a
+//! column if the fields have the same name, type and attributes (including
+//! conversions, aliases and defaults).
+//!
+//! # Reading rows one at a time
+//! [`FromDataFrame::iter_df`] reads the rows one at a time instead of
+//! collecting them into a `Vec`. The derived impl only streams the columns
+//! which hold scalars, strings and lists. Columns which hold nested structures
+//! or tuples are read through [`FromColumn`], which returns every value at
+//! once. The rows of flattened fields are read out of a `DataFrame` of their
+//! own, which the iterator can't borrow from, so they are collected as well.
+//! For these columns, the values are held in memory next to the `DataFrame`
+//! until the iterator is dropped.
+//!
+//! [`FromDataFrame::iter_df`] and [`FromDataFrame::from_df_lenient`] return the
+//! errors as an [`Error`], which records the column and the index of the row
+//! that could not be read. [`FromDataFrame::from_df`] converts it into a
+//! [`PolarsError`]. Manual implementations only need
+//! [`FromDataFrame::from_df`]; the other methods then read every row up front,
+//! and return any error it returns as an [`Error::Polars`] for the whole
+//! `DataFrame`.
 
 use polars::prelude::*;
-use polars_derive_impl;
-pub use polars_derive_impl::{FromDataFrame, IntoDataFrame};
+pub use polars_derive_impl::{FromDataFrame, FromDataFrameRef, IntoDataFrame};
+
+pub use column::{FromColumn, IntoColumn};
+pub use error::{Error, RowError};
+
+mod column;
+mod error;
 
 /// This trait allows the user to convert an iterator of a structure into a
 /// [`DataFrame`].
@@ -27,19 +143,104 @@ pub use polars_derive_impl::{FromDataFrame, IntoDataFrame};
 ///  - `#[df(serialize_with_borrow = <path>)]`: similar to `serialize_with`, but
 ///    borrows the item instead. this is useful for methods that only allow &str
 ///    and not String, for example
+///  - `#[df(try_into = <type>)]`, `#[df(try_serialize_with = <path>)]` and
+///    `#[df(try_serialize_with_borrow = <path>)]`: same as `into`,
+///    `serialize_with` and `serialize_with_borrow`, but the conversion returns
+///    a `Result`. if it fails, [`IntoDataFrame::into_df`] and
+///    [`IntoDataFrame::try_into_series`] return an error, while
+///    [`IntoDataFrame::into_series`] panics
 ///  - `#[df(optional = <bool>)]`: indicates explicitly whether the data in this
 ///    column is considered optional or not. will cause type errors if this does
 ///    not match the type of the field
+///  - `#[df(flatten)]`: the field must be another structure that derives
+///    `IntoDataFrame`; its columns are inlined into this `DataFrame` instead of
+///    being stored as a `Struct` column
+///  - `#[df(flatten, prefix = "<prefix>")]`: same as `flatten`, but the names
+///    of the inlined columns start with `<prefix>`
+///  - `#[df(rename = "<name>")]`: stores this field in the column `<name>`
+///    instead of in a column with the same name as the field
+///  - `#[df(timezone = "<tz>")]`: sets the time zone of the `Datetime` column
+///    for this field (ex.: `Europe/Berlin` or `+02:00`) instead of `UTC`. only
+///    valid for fields which are in a time zone, like `DateTime<Tz>`, and not
+///    for naive datetimes. for `DateTime<Utc>`, `DateTime<FixedOffset>` and
+///    `OffsetDateTime`, it has to be `UTC` or an offset
+///  - `#[df(time_unit = "<unit>")]`: sets the time unit of the `Datetime` or
+///    `Duration` column for this field, which is one of `ms` (the default),
+///    `us` or `ns`. only valid for datetime and duration fields
+///  - `#[df(skip)]` or `#[df(skip_serializing)]`: this field is not stored in
+///    the `DataFrame`. its type does not need to be supported by Polars if it
+///    is skipped entirely using `skip`
+///
+/// The following parameters can be attached to the structure or enum:
+///
+///  - `#[df(rename_all = "<rule>")]`: renames all columns (or the variants of
+///    an enum) according to `<rule>`, which is one of `lowercase`,
+///    `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`,
+///    `SCREAMING_SNAKE_CASE`, `kebab-case` or `SCREAMING-KEBAB-CASE`. fields
+///    with an explicit `rename` are not affected by it. tuple structures,
+///    whose columns are named `column_0`, `column_1`, and so on, can't use it
+///  - `#[df(bound = "<bounds>")]`: replaces the bounds on the type parameters
+///    of the derived impl
+///  - `#[df(transparent)]`: the structure must have a single field (ex.:
+///    `struct UserId(u64)`). it implements [`IntoColumn`] instead, and is
+///    stored in the same way as its field
+///  - `#[df(dtype = Utf8)]` or `#[df(dtype = Categorical)]`: the data type of
+///    the column of an enum whose variants have no fields, or of the tag
+///    column. defaults to `Utf8`. `Categorical` needs the `dtype-categorical`
+///    feature
+///  - `#[df(tag = "<name>")]`: the name of the tag column of an enum whose
+///    variants have fields. defaults to `type`
 ///
-/// If the data type is not specified explicitly using `#[df(dtype)]`, it will
-/// be inferred from the type of the field. Fields can be `Option<T>`, but inner
-/// `Option`s (ex.: `Vec<Option<T>>`) are currently unsupported.
+/// `#[df(rename = "<name>")]` can also be attached to a variant, which is then
+/// stored as `<name>`.
+///
+/// See the [crate docs](crate) for the types which are supported, generic
+/// structures, enums and borrowed rows.
 pub trait IntoDataFrame {
     fn schema() -> Schema;
 
+    /// Converts the rows into one [`Series`] per column.
+    ///
+    /// # Panics
+    /// The derived impl panics if one of the fields can't be converted (see
+    /// `try_into` and `try_serialize_with`). [`IntoDataFrame::into_df`] and
+    /// [`IntoDataFrame::try_into_series`] return an error instead.
     fn into_series(rows: impl Iterator<Item = Self>) -> Vec<Series>;
     fn into_df(rows: impl Iterator<Item = Self>) -> PolarsResult<DataFrame> {
-        DataFrame::new(Self::into_series(rows))
+        DataFrame::new(Self::try_into_series(rows)?)
+    }
+
+    /// Same as [`IntoDataFrame::into_series`], but returns an error instead of
+    /// panicking if one of the fields can't be converted (see `try_into` and
+    /// `try_serialize_with`), which refers to the field and the index of the
+    /// row. The derive macro implements it; other impls only need to override
+    /// it if they can fail.
+    fn try_into_series(rows: impl Iterator<Item = Self>) -> PolarsResult<Vec<Series>> {
+        Ok(Self::into_series(rows))
+    }
+
+    /// Same as [`IntoDataFrame::into_series`], but only borrows the rows.
+    ///
+    /// # Panics
+    /// Like [`IntoDataFrame::into_series`], the derived impl panics if one of
+    /// the fields can't be converted.
+    fn to_series<'a>(rows: impl Iterator<Item = &'a Self>) -> Vec<Series>
+    where
+        Self: 'a;
+    /// Same as [`IntoDataFrame::into_df`], but only borrows the rows.
+    fn to_df(rows: &[Self]) -> PolarsResult<DataFrame>
+    where
+        Self: Sized,
+    {
+        DataFrame::new(Self::try_to_series(rows.iter())?)
+    }
+
+    /// Same as [`IntoDataFrame::try_into_series`], but only borrows the rows.
+    fn try_to_series<'a>(rows: impl Iterator<Item = &'a Self>) -> PolarsResult<Vec<Series>>
+    where
+        Self: 'a,
+    {
+        Ok(Self::to_series(rows))
     }
 }
 
@@ -61,32 +262,737 @@ pub trait IntoDataFrame {
 ///    `TryFrom<String>`, for example
 ///  - `#[df(deserialize_with = <path>)]`: items in this column will be
 ///    converted using the method at `<path>` when they are being read from the
-///    `DataFrame`
+///    `DataFrame`. like with `try_from`, the items of a `Datetime` column are
+///    the timestamps as they are stored, in the time unit of the column
 ///  - `#[df(deserialize_with_borrow = <path>)]`: items in this column will be
 ///    borrowed converted using the method at `<path>` when they are being read
 ///    from the `DataFrame`
 ///  - `#[df(optional = <bool>)]`: indicates explicitly whether the data in this
 ///    column is considered optional or not. will cause type errors if this does
 ///    not match the type of the field
+///  - `#[df(flatten)]`: the field must be another structure that derives
+///    `FromDataFrame`; it is read from columns that were inlined into this
+///    `DataFrame` instead of from a `Struct` column
+///  - `#[df(flatten, prefix = "<prefix>")]`: same as `flatten`, but the inlined
+///    columns are the ones whose names start with `<prefix>`
+///  - `#[df(rename = "<name>")]`: reads this field from the column `<name>`
+///    instead of from a column with the same name as the field
+///  - `#[df(alias = "<name>")]`: if the column for this field is missing, it
+///    is read from the column `<name>` instead. can be repeated, in which case
+///    the aliases are tried in order
+///  - `#[df(skip)]` or `#[df(skip_deserializing)]`: this field is not read
+///    from the `DataFrame`, and is filled using `Default::default()` instead
+///  - `#[df(default)]`: if the column for this field is missing, or if it
+///    holds a null for a field which is not an `Option`, the field is filled
+///    using `Default::default()` instead of returning an error. when used
+///    together with `skip` or `skip_deserializing`, this has no effect
+///  - `#[df(default = <path>)]`: same as `default`, but the field is filled by
+///    calling `<path>()` instead of `Default::default()`. this also applies to
+///    fields which are skipped
 ///
-/// If the data type is not specified explicitly using `#[df(dtype)]`, it will
-/// be inferred from the type of the field. Fields can be `Option<T>`, but inner
-/// `Option`s (ex.: `Vec<Option<T>>`) are currently unsupported.
+/// The parameters which can be attached to the structure or enum are the same
+/// as for [`IntoDataFrame`]. See the [crate docs](crate) for the types which
+/// are supported and how the rows are read.
 pub trait FromDataFrame: Sized {
     fn from_df(df: &DataFrame) -> PolarsResult<Vec<Self>>;
+
+    /// Returns an iterator which reads the rows of `df` one at a time. Returns
+    /// an error right away if any of the columns are missing or have the wrong
+    /// data type. Some of the columns are still read up front (see the
+    /// [crate docs](crate#reading-rows-one-at-a-time)).
+    ///
+    /// The items are [`Error`]s so that the error for a row keeps the column
+    /// and the index of the row. Use `?` or `.into()` to get a
+    /// [`PolarsError`].
+    fn iter_df(df: &DataFrame) -> Result<impl Iterator<Item = Result<Self, Error>>, Error> {
+        Ok(Self::from_df(df)?.into_iter().map(Ok))
+    }
+
+    /// Same as [`FromDataFrame::from_df`], but skips the rows which can't be
+    /// read (ex.: because a conversion failed or a value is missing) instead
+    /// of returning an error. The errors for the skipped rows are returned
+    /// alongside the rows that were read. This includes the values of nested
+    /// and flattened structures. Errors which affect every row, like a missing
+    /// column, are still returned right away.
+    fn from_df_lenient(df: &DataFrame) -> Result<(Vec<Self>, Vec<RowError>), Error> {
+        Ok(partition_rows(Self::iter_df(df)?))
+    }
+}
+
+/// This trait allows the user to convert a [`DataFrame`] into a list of a
+/// structure which borrows from the `DataFrame`.
+///
+/// The derive macro takes the same parameters as [`FromDataFrame`]. Fields of
+/// type `&'a str` and `&'a [u8]` (or `Option`s of them) are read from `Utf8`
+/// and `Binary` columns without copying the data, where `'a` is the lifetime
+/// of the structure. Other fields are read in the same way as for
+/// [`FromDataFrame`]. Structures with more than one lifetime are not
+/// supported, and neither are enums whose variants have no fields.
+pub trait FromDataFrameRef<'a>: Sized {
+    /// Same as [`FromDataFrame::iter_df`].
+    fn iter_df_ref(df: &'a DataFrame) -> Result<impl Iterator<Item = Result<Self, Error>>, Error>;
+    fn from_df_ref(df: &'a DataFrame) -> PolarsResult<Vec<Self>> {
+        Ok(Self::iter_df_ref(df)?.collect::<Result<_, Error>>()?)
+    }
+
+    /// Same as [`FromDataFrame::from_df_lenient`].
+    fn from_df_ref_lenient(df: &'a DataFrame) -> Result<(Vec<Self>, Vec<RowError>), Error> {
+        Ok(partition_rows(Self::iter_df_ref(df)?))
+    }
+}
+
+/// Splits the results of reading each row into the rows which were read and
+/// the errors for the rest.
+fn partition_rows<T>(rows: impl Iterator<Item = Result<T, Error>>) -> (Vec<T>, Vec<RowError>) {
+    let mut values = vec![];
+    let mut errors = vec![];
+
+    for (row, result) in rows.enumerate() {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => errors.push(RowError { row, error }),
+        }
+    }
+
+    (values, errors)
 }
 
 pub mod helpers {
-    use polars::export::chrono::NaiveDateTime;
+    use std::str::FromStr;
+
+    use polars::export::chrono::{
+        DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+        TimeZone, Timelike, Utc,
+    };
+    use polars::prelude::*;
+    #[cfg(feature = "std-time")]
+    use std::time::SystemTime;
     use thiserror::Error;
 
     #[derive(Error, Debug)]
     pub enum Error {
         #[error("invalid datetime")]
         InvalidDatetime,
+        #[error("invalid date")]
+        InvalidDate,
+        #[error("invalid time")]
+        InvalidTime,
+        #[error("invalid duration")]
+        InvalidDuration,
+        #[error("invalid time zone: {0}")]
+        InvalidTimeZone(String),
     }
 
+    /// Reads a `NaiveDateTime` out of a timestamp in milliseconds.
+    #[allow(deprecated)]
     pub fn deserialize_datetime(timestamp: i64) -> Result<NaiveDateTime, Error> {
         NaiveDateTime::from_timestamp_millis(timestamp).ok_or(Error::InvalidDatetime)
     }
+
+    /// Reads a `NaiveDateTime` out of a timestamp in microseconds.
+    pub fn deserialize_datetime_us(timestamp: i64) -> Result<NaiveDateTime, Error> {
+        DateTime::from_timestamp_micros(timestamp)
+            .map(|dt| dt.naive_utc())
+            .ok_or(Error::InvalidDatetime)
+    }
+
+    /// Reads a `NaiveDateTime` out of a timestamp in nanoseconds.
+    pub fn deserialize_datetime_ns(timestamp: i64) -> Result<NaiveDateTime, Error> {
+        Ok(DateTime::from_timestamp_nanos(timestamp).naive_utc())
+    }
+
+    /// Reads a `NaiveDateTime` out of a timestamp in `unit`, which is the
+    /// time unit of the column that it was read from.
+    pub fn deserialize_naive_datetime(
+        timestamp: i64,
+        unit: TimeUnit,
+    ) -> Result<NaiveDateTime, Error> {
+        match unit {
+            TimeUnit::Milliseconds => deserialize_datetime(timestamp),
+            TimeUnit::Microseconds => deserialize_datetime_us(timestamp),
+            TimeUnit::Nanoseconds => deserialize_datetime_ns(timestamp),
+        }
+    }
+
+    fn datetime_from_timestamp(timestamp: i64, unit: TimeUnit) -> Result<DateTime<Utc>, Error> {
+        match unit {
+            TimeUnit::Milliseconds => DateTime::from_timestamp_millis(timestamp),
+            TimeUnit::Microseconds => DateTime::from_timestamp_micros(timestamp),
+            TimeUnit::Nanoseconds => Some(DateTime::from_timestamp_nanos(timestamp)),
+        }
+        .ok_or(Error::InvalidDatetime)
+    }
+
+    /// Reads a `DateTime<Utc>` out of a timestamp in `unit`.
+    pub fn deserialize_datetime_utc(
+        timestamp: i64,
+        unit: TimeUnit,
+    ) -> Result<DateTime<Utc>, Error> {
+        datetime_from_timestamp(timestamp, unit)
+    }
+
+    /// Reads a `DateTime<FixedOffset>` out of a timestamp in `unit`. `tz` is
+    /// the time zone of the column, either `UTC` or an offset like `+02:00`.
+    pub fn deserialize_datetime_fixed(
+        timestamp: i64,
+        unit: TimeUnit,
+        tz: &str,
+    ) -> Result<DateTime<FixedOffset>, Error> {
+        Ok(datetime_from_timestamp(timestamp, unit)?.with_timezone(&parse_offset(tz)?))
+    }
+
+    /// Parses `tz`, the time zone of a column, which is either `UTC` or an
+    /// offset like `+02:00`.
+    fn parse_offset(tz: &str) -> Result<FixedOffset, Error> {
+        match tz {
+            "UTC" => Ok(Utc.fix()),
+            tz => FixedOffset::from_str(tz).map_err(|_| Error::InvalidTimeZone(tz.to_owned())),
+        }
+    }
+
+    /// Reads a `DateTime<Tz>` out of a timestamp in `unit`, for any time zone
+    /// which can be parsed out of its name, such as `chrono_tz::Tz`. `tz` is
+    /// the time zone of the column.
+    pub fn deserialize_datetime_tz<Tz: TimeZone + FromStr>(
+        timestamp: i64,
+        unit: TimeUnit,
+        tz: &str,
+    ) -> Result<DateTime<Tz>, Error> {
+        let tz = Tz::from_str(tz).map_err(|_| Error::InvalidTimeZone(tz.to_owned()))?;
+
+        Ok(datetime_from_timestamp(timestamp, unit)?.with_timezone(&tz))
+    }
+
+    /// Returns the number of nanoseconds in `unit`.
+    #[cfg(any(feature = "std-time", feature = "time"))]
+    fn nanos_per_unit(unit: TimeUnit) -> i128 {
+        match unit {
+            TimeUnit::Milliseconds => 1_000_000,
+            TimeUnit::Microseconds => 1_000,
+            TimeUnit::Nanoseconds => 1,
+        }
+    }
+
+    /// Converts a number of nanoseconds since the UNIX epoch into a timestamp
+    /// in `unit`, rounding down like chrono does.
+    #[cfg(any(feature = "std-time", feature = "time"))]
+    fn timestamp_from_nanos(nanos: i128, unit: TimeUnit) -> Result<i64, Error> {
+        i64::try_from(nanos.div_euclid(nanos_per_unit(unit))).map_err(|_| Error::InvalidDatetime)
+    }
+
+    /// Reads a `SystemTime` out of a timestamp in `unit`.
+    #[cfg(feature = "std-time")]
+    pub fn deserialize_system_time(timestamp: i64, unit: TimeUnit) -> Result<SystemTime, Error> {
+        let nanos = i128::from(timestamp) * nanos_per_unit(unit);
+        let since_epoch = nanos.unsigned_abs();
+        let since_epoch = std::time::Duration::new(
+            u64::try_from(since_epoch / 1_000_000_000).map_err(|_| Error::InvalidDatetime)?,
+            (since_epoch % 1_000_000_000) as u32,
+        );
+
+        if nanos < 0 {
+            SystemTime::UNIX_EPOCH.checked_sub(since_epoch)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_add(since_epoch)
+        }
+        .ok_or(Error::InvalidDatetime)
+    }
+
+    /// Reads an `OffsetDateTime` out of a timestamp in `unit`. `tz` is the
+    /// time zone of the column, either `UTC` or an offset like `+02:00`.
+    #[cfg(feature = "time")]
+    pub fn deserialize_offset_datetime(
+        timestamp: i64,
+        unit: TimeUnit,
+        tz: &str,
+    ) -> Result<time::OffsetDateTime, Error> {
+        let offset = time::UtcOffset::from_whole_seconds(parse_offset(tz)?.local_minus_utc())
+            .map_err(|_| Error::InvalidTimeZone(tz.to_owned()))?;
+
+        time::OffsetDateTime::from_unix_timestamp_nanos(
+            i128::from(timestamp) * nanos_per_unit(unit),
+        )
+        .ok()
+        .and_then(|datetime| datetime.checked_to_offset(offset))
+        .ok_or(Error::InvalidDatetime)
+    }
+
+    /// Reads values out of a timestamp in `unit`, which is how Polars stores
+    /// datetimes. `unit` is the time unit of the column. `DateTime<Tz>` is not
+    /// read through this trait, since it also needs the time zone of the
+    /// column.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be read from a datetime column",
+        note = "`NaiveDateTime` and `std::time::SystemTime` fields are read through this trait, \
+                name a type of your own through its module (like `self::NaiveDateTime`) to read \
+                it through `FromColumn`"
+    )]
+    pub trait FromTimestamp: Sized {
+        fn from_timestamp(timestamp: i64, unit: TimeUnit) -> Result<Self, Error>;
+    }
+
+    impl FromTimestamp for NaiveDateTime {
+        fn from_timestamp(timestamp: i64, unit: TimeUnit) -> Result<Self, Error> {
+            deserialize_naive_datetime(timestamp, unit)
+        }
+    }
+
+    #[cfg(feature = "std-time")]
+    impl FromTimestamp for SystemTime {
+        fn from_timestamp(timestamp: i64, unit: TimeUnit) -> Result<Self, Error> {
+            deserialize_system_time(timestamp, unit)
+        }
+    }
+
+    /// Converts datetimes into timestamps in `unit`, which is how Polars
+    /// stores them. `None` is stored as a null. Returns an error if the
+    /// timestamp doesn't fit in 64 bits, which happens for nanoseconds outside
+    /// of the years 1677 to 2262.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be stored in a datetime column",
+        note = "fields whose type is named `NaiveDateTime`, `DateTime`, `SystemTime` or \
+                `OffsetDateTime` are stored through this trait, name a type of your own through \
+                its module (like `self::DateTime`) to store it through `IntoColumn`"
+    )]
+    pub trait ToTimestamp {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error>;
+    }
+
+    impl ToTimestamp for NaiveDateTime {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            self.and_utc().to_timestamp(unit)
+        }
+    }
+
+    impl<Tz: TimeZone> ToTimestamp for DateTime<Tz> {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            match unit {
+                TimeUnit::Milliseconds => Ok(Some(self.timestamp_millis())),
+                TimeUnit::Microseconds => Ok(Some(self.timestamp_micros())),
+                TimeUnit::Nanoseconds => self
+                    .timestamp_nanos_opt()
+                    .map(Some)
+                    .ok_or(Error::InvalidDatetime),
+            }
+        }
+    }
+
+    #[cfg(feature = "std-time")]
+    impl ToTimestamp for SystemTime {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            let nanos = match self.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(after) => i128::try_from(after.as_nanos()),
+                Err(before) => i128::try_from(before.duration().as_nanos()).map(|nanos| -nanos),
+            }
+            .map_err(|_| Error::InvalidDatetime)?;
+
+            timestamp_from_nanos(nanos, unit).map(Some)
+        }
+    }
+
+    #[cfg(feature = "time")]
+    impl ToTimestamp for time::OffsetDateTime {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            timestamp_from_nanos(self.unix_timestamp_nanos(), unit).map(Some)
+        }
+    }
+
+    impl<T: ToTimestamp> ToTimestamp for Option<T> {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            match self {
+                Some(value) => value.to_timestamp(unit),
+                None => Ok(None),
+            }
+        }
+    }
+
+    impl<T: ToTimestamp + ?Sized> ToTimestamp for &T {
+        fn to_timestamp(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            (**self).to_timestamp(unit)
+        }
+    }
+
+    /// The number of days from 0001-01-01 to the UNIX epoch.
+    const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+    /// Reads a `NaiveDate` out of the number of days since the UNIX epoch.
+    pub fn deserialize_date(days: i32) -> Result<NaiveDate, Error> {
+        days.checked_add(UNIX_EPOCH_DAYS_FROM_CE)
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
+            .ok_or(Error::InvalidDate)
+    }
+
+    /// Reads a `NaiveTime` out of the number of nanoseconds since midnight.
+    pub fn deserialize_time(nanos: i64) -> Result<NaiveTime, Error> {
+        let secs =
+            u32::try_from(nanos.div_euclid(1_000_000_000)).map_err(|_| Error::InvalidTime)?;
+        let nanos = nanos.rem_euclid(1_000_000_000) as u32;
+
+        NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos).ok_or(Error::InvalidTime)
+    }
+
+    /// Reads a `Duration` out of a number of `unit`.
+    pub fn deserialize_duration(duration: i64, unit: TimeUnit) -> Result<Duration, Error> {
+        match unit {
+            TimeUnit::Milliseconds => Duration::try_milliseconds(duration),
+            TimeUnit::Microseconds => Some(Duration::microseconds(duration)),
+            TimeUnit::Nanoseconds => Some(Duration::nanoseconds(duration)),
+        }
+        .ok_or(Error::InvalidDuration)
+    }
+
+    /// Reads a `std::time::Duration` out of a number of `unit`. Negative
+    /// durations are invalid.
+    #[cfg(feature = "std-time")]
+    pub fn deserialize_std_duration(
+        duration: i64,
+        unit: TimeUnit,
+    ) -> Result<std::time::Duration, Error> {
+        let duration = u64::try_from(duration).map_err(|_| Error::InvalidDuration)?;
+
+        Ok(match unit {
+            TimeUnit::Milliseconds => std::time::Duration::from_millis(duration),
+            TimeUnit::Microseconds => std::time::Duration::from_micros(duration),
+            TimeUnit::Nanoseconds => std::time::Duration::from_nanos(duration),
+        })
+    }
+
+    /// Converts dates into the number of days since the UNIX epoch, which is
+    /// how Polars stores them. `None` is stored as a null.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be stored in a date column",
+        note = "fields whose type is named `NaiveDate` or `time::Date` are stored through this \
+                trait, name a type of your own through its module (like `self::NaiveDate`) to \
+                store it through `IntoColumn`"
+    )]
+    pub trait ToDate {
+        fn to_date(&self) -> Option<i32>;
+    }
+
+    impl ToDate for NaiveDate {
+        fn to_date(&self) -> Option<i32> {
+            Some(self.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE)
+        }
+    }
+
+    /// The Julian day of the UNIX epoch.
+    #[cfg(feature = "time")]
+    const UNIX_EPOCH_JULIAN_DAY: i32 = 2_440_588;
+
+    #[cfg(feature = "time")]
+    impl ToDate for time::Date {
+        fn to_date(&self) -> Option<i32> {
+            Some(self.to_julian_day() - UNIX_EPOCH_JULIAN_DAY)
+        }
+    }
+
+    impl<T: ToDate> ToDate for Option<T> {
+        fn to_date(&self) -> Option<i32> {
+            self.as_ref().and_then(ToDate::to_date)
+        }
+    }
+
+    impl<T: ToDate + ?Sized> ToDate for &T {
+        fn to_date(&self) -> Option<i32> {
+            (**self).to_date()
+        }
+    }
+
+    /// Converts times of day into the number of nanoseconds since midnight,
+    /// which is how Polars stores them. `None` is stored as a null.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be stored in a time column",
+        note = "fields whose type is named `NaiveTime` are stored through this trait, name a \
+                type of your own through its module (like `self::NaiveTime`) to store it through \
+                `IntoColumn`"
+    )]
+    pub trait ToTime {
+        fn to_time(&self) -> Option<i64>;
+    }
+
+    impl ToTime for NaiveTime {
+        fn to_time(&self) -> Option<i64> {
+            Some(
+                i64::from(self.num_seconds_from_midnight()) * 1_000_000_000
+                    + i64::from(self.nanosecond()),
+            )
+        }
+    }
+
+    impl<T: ToTime> ToTime for Option<T> {
+        fn to_time(&self) -> Option<i64> {
+            self.as_ref().and_then(ToTime::to_time)
+        }
+    }
+
+    impl<T: ToTime + ?Sized> ToTime for &T {
+        fn to_time(&self) -> Option<i64> {
+            (**self).to_time()
+        }
+    }
+
+    /// Converts durations into a number of `unit`, which is how Polars stores
+    /// them. `None` is stored as a null. Returns an error if the number
+    /// doesn't fit in 64 bits.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be stored in a duration column",
+        note = "fields whose type is named `Duration` are stored through this trait, name a \
+                type of your own through its module (like `self::Duration`) to store it \
+                through `IntoColumn`"
+    )]
+    pub trait ToDuration {
+        fn to_duration(&self, unit: TimeUnit) -> Result<Option<i64>, Error>;
+    }
+
+    impl ToDuration for Duration {
+        fn to_duration(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            match unit {
+                TimeUnit::Milliseconds => Some(self.num_milliseconds()),
+                TimeUnit::Microseconds => self.num_microseconds(),
+                TimeUnit::Nanoseconds => self.num_nanoseconds(),
+            }
+            .map(Some)
+            .ok_or(Error::InvalidDuration)
+        }
+    }
+
+    #[cfg(feature = "std-time")]
+    impl ToDuration for std::time::Duration {
+        fn to_duration(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            let duration = match unit {
+                TimeUnit::Milliseconds => self.as_millis(),
+                TimeUnit::Microseconds => self.as_micros(),
+                TimeUnit::Nanoseconds => self.as_nanos(),
+            };
+
+            i64::try_from(duration)
+                .map(Some)
+                .map_err(|_| Error::InvalidDuration)
+        }
+    }
+
+    #[cfg(feature = "time")]
+    impl ToDuration for time::Duration {
+        fn to_duration(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            i64::try_from(self.whole_nanoseconds() / nanos_per_unit(unit))
+                .map(Some)
+                .map_err(|_| Error::InvalidDuration)
+        }
+    }
+
+    impl<T: ToDuration> ToDuration for Option<T> {
+        fn to_duration(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            match self {
+                Some(value) => value.to_duration(unit),
+                None => Ok(None),
+            }
+        }
+    }
+
+    impl<T: ToDuration + ?Sized> ToDuration for &T {
+        fn to_duration(&self, unit: TimeUnit) -> Result<Option<i64>, Error> {
+            (**self).to_duration(unit)
+        }
+    }
+
+    /// Reads values out of the number of days since the UNIX epoch, which is
+    /// how Polars stores dates.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be read from a date column",
+        note = "`NaiveDate` and `time::Date` fields are read through this trait, name a type of \
+                your own through its module (like `self::NaiveDate`) to read it through \
+                `FromColumn`"
+    )]
+    pub trait FromDate: Sized {
+        fn from_date(days: i32) -> Result<Self, Error>;
+    }
+
+    impl FromDate for NaiveDate {
+        fn from_date(days: i32) -> Result<Self, Error> {
+            deserialize_date(days)
+        }
+    }
+
+    #[cfg(feature = "time")]
+    impl FromDate for time::Date {
+        fn from_date(days: i32) -> Result<Self, Error> {
+            days.checked_add(UNIX_EPOCH_JULIAN_DAY)
+                .and_then(|day| time::Date::from_julian_day(day).ok())
+                .ok_or(Error::InvalidDate)
+        }
+    }
+
+    /// Reads values out of the number of nanoseconds since midnight, which is
+    /// how Polars stores times of day.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be read from a time column",
+        note = "`NaiveTime` fields are read through this trait, name a type of your own through \
+                its module (like `self::NaiveTime`) to read it through `FromColumn`"
+    )]
+    pub trait FromTime: Sized {
+        fn from_time(nanos: i64) -> Result<Self, Error>;
+    }
+
+    impl FromTime for NaiveTime {
+        fn from_time(nanos: i64) -> Result<Self, Error> {
+            deserialize_time(nanos)
+        }
+    }
+
+    /// Reads values out of a number of `unit`, which is how Polars stores
+    /// durations. `unit` is the time unit of the column.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be read from a duration column",
+        note = "chrono's `Duration`, `std::time::Duration` and `time::Duration` fields are read \
+                through this trait, name a type of your own through its module (like \
+                `self::Duration`) to read it through `FromColumn`"
+    )]
+    pub trait FromDuration: Sized {
+        fn from_duration(duration: i64, unit: TimeUnit) -> Result<Self, Error>;
+    }
+
+    impl FromDuration for Duration {
+        fn from_duration(duration: i64, unit: TimeUnit) -> Result<Self, Error> {
+            deserialize_duration(duration, unit)
+        }
+    }
+
+    #[cfg(feature = "std-time")]
+    impl FromDuration for std::time::Duration {
+        fn from_duration(duration: i64, unit: TimeUnit) -> Result<Self, Error> {
+            deserialize_std_duration(duration, unit)
+        }
+    }
+
+    #[cfg(feature = "time")]
+    impl FromDuration for time::Duration {
+        fn from_duration(duration: i64, unit: TimeUnit) -> Result<Self, Error> {
+            Ok(match unit {
+                TimeUnit::Milliseconds => time::Duration::milliseconds(duration),
+                TimeUnit::Microseconds => time::Duration::microseconds(duration),
+                TimeUnit::Nanoseconds => time::Duration::nanoseconds(duration),
+            })
+        }
+    }
+}
+
+/// Functions and types which are used by the code that the derive macros
+/// generate. They are not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    use polars::error::ErrString;
+    use polars::prelude::*;
+
+    /// Returns the first column of `df` whose name is in `names`. If there is
+    /// none, returns the error for a missing column named `names[0]`. Fields
+    /// which have aliases have more than one name.
+    pub fn column<'a>(df: &'a DataFrame, names: &[&str]) -> Result<&'a Series, crate::Error> {
+        names
+            .iter()
+            .find_map(|name| df.column(name).ok())
+            .ok_or_else(|| crate::Error::MissingColumn {
+                column: names[0].to_owned(),
+            })
+    }
+
+    /// Returns the error for a column which can't be read as `expected`.
+    pub fn dtype_mismatch(column: &str, expected: DataType, series: &Series) -> crate::Error {
+        crate::Error::DtypeMismatch {
+            column: column.to_owned(),
+            expected,
+            actual: series.dtype().clone(),
+        }
+    }
+
+    /// Reads the tags of the rows of an enum, out of a `Utf8` or a
+    /// `Categorical` column. Unlike an iterator, the tags borrow from this
+    /// instead of from the column, so the rows can be read lazily even if the
+    /// column has to be converted.
+    pub struct Tags {
+        tags: Utf8Chunked,
+        row: usize,
+    }
+
+    impl Tags {
+        pub fn new(series: &Series) -> PolarsResult<Self> {
+            let tags = series.cast(&DataType::Utf8)?;
+
+            Ok(Self {
+                tags: tags.utf8()?.clone(),
+                row: 0,
+            })
+        }
+
+        /// Returns the tag of the next row, or `None` if there are no rows
+        /// left.
+        pub fn next_tag(&mut self) -> Option<Option<&str>> {
+            if self.row >= self.tags.len() {
+                return None;
+            }
+
+            let tag = self.tags.get(self.row);
+            self.row += 1;
+            Some(tag)
+        }
+    }
+
+    /// Returns the error for a field which could not be converted while it was
+    /// being stored in the column `name`.
+    pub fn conversion_error(name: &str, row: usize, err: impl std::fmt::Display) -> PolarsError {
+        PolarsError::ComputeError(ErrString::from(format!(
+            "failed to convert field {name} in row {row}: {err}"
+        )))
+    }
+
+    /// Returns `err` with `prefix` added to the name of its column. This is
+    /// used for errors in nested structures, whose columns are named
+    /// relative to the structure.
+    pub fn prefix_error(prefix: &str, err: crate::Error) -> crate::Error {
+        err.with_prefix(prefix)
+    }
+
+    /// Returns the columns of `df` whose names start with `prefix`, with the
+    /// prefix removed from their names. This is used to read structures which
+    /// were flattened using `#[df(flatten, prefix = "...")]`.
+    pub fn strip_prefix(df: &DataFrame, prefix: &str) -> PolarsResult<DataFrame> {
+        if prefix.is_empty() {
+            return Ok(df.clone());
+        }
+
+        let columns = df
+            .get_columns()
+            .iter()
+            .filter_map(|s| {
+                let name = s.name().strip_prefix(prefix)?;
+                let mut s = s.clone();
+                s.rename(name);
+                Some(s)
+            })
+            .collect();
+
+        DataFrame::new(columns)
+    }
+
+    /// Returns `rows`, the values read out of the `Struct` column `series`
+    /// by `FromColumn::from_required_column`, where each of the rows in which
+    /// every field is null is replaced by `None`.
+    #[cfg(feature = "dtype-struct")]
+    pub fn struct_nulls<T>(
+        series: &Series,
+        rows: Vec<Result<Option<T>, crate::Error>>,
+    ) -> Vec<Result<Option<T>, crate::Error>> {
+        // without fields, there are no rows
+        match series.struct_() {
+            Ok(ca) if !ca.fields().is_empty() => {}
+            _ => return rows,
+        }
+
+        rows.into_iter()
+            .zip(&series.is_null())
+            .map(|(row, is_null)| match is_null {
+                Some(true) => Ok(None),
+                _ => row,
+            })
+            .collect()
+    }
 }
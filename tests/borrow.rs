@@ -0,0 +1,208 @@
+use polars::export::chrono::{NaiveDate, NaiveDateTime};
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Address {
+    street: String,
+    number: Option<u32>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, Copy, PartialEq, Debug)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(transparent)]
+struct Score(f64);
+
+#[derive(Clone, PartialEq, Debug)]
+struct Code(u16);
+
+impl From<Code> for u32 {
+    fn from(code: Code) -> Self {
+        code.0 as u32
+    }
+}
+
+fn shout(value: &str) -> String {
+    value.to_uppercase()
+}
+
+#[derive(IntoDataFrame, Clone, PartialEq, Debug)]
+struct Customer {
+    name: String,
+    nickname: Option<String>,
+    avatar: Vec<u8>,
+    thumbnail: Option<Vec<u8>>,
+    tags: Vec<String>,
+    visits: Option<Vec<u32>>,
+    matrix: Vec<Vec<i64>>,
+    age: u32,
+    joined: NaiveDateTime,
+    address: Address,
+    previous: Option<Address>,
+    #[df(flatten, prefix = "billing_")]
+    billing: Address,
+    status: Status,
+    score: Score,
+    pair: (String, Option<u32>),
+    #[df(into = u32)]
+    code: Code,
+    #[df(dtype = Utf8, serialize_with_borrow = shout)]
+    city: String,
+}
+
+fn customers() -> Vec<Customer> {
+    vec![
+        Customer {
+            name: "alice".to_owned(),
+            nickname: None,
+            avatar: vec![1, 2, 3],
+            thumbnail: Some(vec![4]),
+            tags: vec!["a".to_owned(), "b".to_owned()],
+            visits: None,
+            matrix: vec![vec![1, 2], vec![]],
+            age: 30,
+            joined: NaiveDate::from_ymd_opt(2020, 9, 13)
+                .unwrap()
+                .and_hms_opt(12, 26, 40)
+                .unwrap(),
+            address: Address {
+                street: "main".to_owned(),
+                number: Some(1),
+            },
+            previous: None,
+            billing: Address {
+                street: "side".to_owned(),
+                number: None,
+            },
+            status: Status::Active,
+            score: Score(0.5),
+            pair: ("x".to_owned(), None),
+            code: Code(7),
+            city: "paris".to_owned(),
+        },
+        Customer {
+            name: "bob".to_owned(),
+            nickname: Some("bobby".to_owned()),
+            avatar: vec![],
+            thumbnail: None,
+            tags: vec![],
+            visits: Some(vec![1, 2]),
+            matrix: vec![],
+            age: 40,
+            joined: NaiveDate::from_ymd_opt(2023, 11, 14)
+                .unwrap()
+                .and_hms_opt(22, 13, 20)
+                .unwrap(),
+            address: Address {
+                street: "high".to_owned(),
+                number: None,
+            },
+            previous: Some(Address {
+                street: "low".to_owned(),
+                number: Some(2),
+            }),
+            billing: Address {
+                street: "back".to_owned(),
+                number: Some(3),
+            },
+            status: Status::Inactive,
+            score: Score(1.5),
+            pair: ("y".to_owned(), Some(2)),
+            code: Code(8),
+            city: "rome".to_owned(),
+        },
+    ]
+}
+
+#[test]
+fn test_to_df() {
+    let rows = customers();
+
+    let borrowed = Customer::to_df(&rows).unwrap();
+    let owned = Customer::into_df(rows.clone().into_iter()).unwrap();
+
+    assert_eq!(borrowed.schema(), Customer::schema());
+    assert!(borrowed.frame_equal_missing(&owned));
+
+    let city = borrowed.column("city").unwrap().utf8().unwrap();
+    assert_eq!(city.get(1), Some("ROME"));
+}
+
+#[test]
+fn test_to_series() {
+    let rows = customers();
+
+    // only every other row is written
+    let series = Customer::to_series(rows.iter().step_by(2));
+    let df = DataFrame::new(series).unwrap();
+
+    assert_eq!(df.height(), 1);
+    let name = df.column("name").unwrap().utf8().unwrap();
+    assert_eq!(name.get(0), Some("alice"));
+}
+
+/// Rows which can't be cloned can still be written out when borrowed.
+#[derive(IntoDataFrame, FromDataFrame, PartialEq, Debug)]
+struct Handle {
+    id: u64,
+    path: String,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, PartialEq, Debug)]
+enum Event {
+    Open { handle: Handle },
+    Close { id: u64, reason: Option<String> },
+}
+
+#[test]
+fn test_no_clone() {
+    let events = vec![
+        Event::Open {
+            handle: Handle {
+                id: 1,
+                path: "/tmp/a".to_owned(),
+            },
+        },
+        Event::Close {
+            id: 1,
+            reason: Some("done".to_owned()),
+        },
+    ];
+
+    let df = Event::to_df(&events).unwrap();
+    assert_eq!(df.schema(), Event::schema());
+
+    let list = Event::from_df(&df).unwrap();
+    assert_eq!(list, events);
+}
+
+/// A generic field which is converted by value, so it is cloned when the rows
+/// are borrowed.
+#[derive(Clone, PartialEq, Debug)]
+struct Token<T>(T, String);
+
+fn token_name<T>(token: Token<T>) -> String {
+    token.1
+}
+
+#[derive(IntoDataFrame, PartialEq, Debug)]
+struct Session<T> {
+    #[df(serialize_with = token_name, dtype = Utf8)]
+    token: Token<T>,
+}
+
+#[test]
+fn test_generic_converted() {
+    let sessions = vec![Session {
+        token: Token(7u8, "abc".to_owned()),
+    }];
+
+    let df = Session::to_df(&sessions).unwrap();
+    let token = df.column("token").unwrap().utf8().unwrap();
+    assert_eq!(token.get(0), Some("abc"));
+}
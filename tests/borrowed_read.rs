@@ -0,0 +1,108 @@
+use polars::prelude::*;
+use polars_derive::{FromDataFrameRef, IntoDataFrame};
+
+#[derive(IntoDataFrame, FromDataFrameRef, PartialEq, Debug)]
+struct Page<'a> {
+    url: &'a str,
+    title: Option<&'a str>,
+    body: &'a [u8],
+    #[df(rename = "len")]
+    length: u32,
+    tags: Vec<String>,
+    #[df(default)]
+    lang: Option<&'a str>,
+}
+
+#[test]
+fn test_read() {
+    let mut df = df! {
+        "url" => ["https://a.example", "https://b.example"],
+        "title" => [Some("A"), None],
+        "body" => [b"<html>".as_slice(), b"".as_slice()],
+        "len" => [6u32, 0],
+    }
+    .unwrap();
+    df.with_column(Series::new(
+        "tags",
+        [Series::new("", ["x", "y"]), Series::new("", ["z"])],
+    ))
+    .unwrap();
+
+    let pages = Page::from_df_ref(&df).unwrap();
+    assert_eq!(
+        pages,
+        [
+            Page {
+                url: "https://a.example",
+                title: Some("A"),
+                body: b"<html>",
+                length: 6,
+                tags: vec!["x".to_owned(), "y".to_owned()],
+                lang: None,
+            },
+            Page {
+                url: "https://b.example",
+                title: None,
+                body: b"",
+                length: 0,
+                tags: vec!["z".to_owned()],
+                lang: None,
+            },
+        ]
+    );
+
+    // the strings point into the DataFrame instead of being copied
+    let urls = df.column("url").unwrap().utf8().unwrap();
+    let value = urls.get(0).unwrap();
+    assert_eq!(pages[0].url.as_ptr(), value.as_ptr());
+}
+
+#[test]
+fn test_round_trip() {
+    let pages = [Page {
+        url: "https://c.example",
+        title: None,
+        body: &[1, 2, 3],
+        length: 3,
+        tags: vec![],
+        lang: Some("en"),
+    }];
+
+    let df = Page::to_df(&pages).unwrap();
+    assert_eq!(df.schema(), Page::schema());
+
+    let list = Page::from_df_ref(&df).unwrap();
+    assert_eq!(list, pages);
+}
+
+#[derive(FromDataFrameRef, PartialEq, Debug)]
+enum Token<'a> {
+    Word { text: &'a str },
+    Number { value: i64 },
+}
+
+#[derive(FromDataFrameRef, PartialEq, Debug)]
+struct Count {
+    value: i64,
+}
+
+#[test]
+fn test_enum() {
+    let df = df! {
+        "type" => ["Word", "Number"],
+        "text" => [Some("hi"), None],
+        "value" => [None, Some(5i64)],
+    }
+    .unwrap();
+
+    let tokens = Token::from_df_ref(&df).unwrap();
+    assert_eq!(
+        tokens,
+        [Token::Word { text: "hi" }, Token::Number { value: 5 }]
+    );
+
+    // structures without a lifetime don't borrow anything, so the DataFrame
+    // can be dropped right away
+    let counts = Count::from_df_ref(&df! { "value" => [1i64, 2] }.unwrap()).unwrap();
+    assert_eq!(counts, [Count { value: 1 }, Count { value: 2 }]);
+}
@@ -0,0 +1,32 @@
+// not every test uses all of the helpers
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+
+/// Writes `rows` into a `DataFrame`, checks that it has the schema of `T`,
+/// and that reading it back returns the same rows. Returns the `DataFrame`
+/// for further checks.
+pub fn round_trip<T>(rows: &[T]) -> DataFrame
+where
+    T: IntoDataFrame + FromDataFrame + Clone + PartialEq + Debug,
+{
+    let df = T::into_df(rows.iter().cloned()).unwrap();
+    assert_eq!(df.schema(), T::schema());
+
+    let list = T::from_df(&df).unwrap();
+    assert_eq!(list, rows);
+    df
+}
+
+/// Checks that the schema of `T` has exactly these columns, in this order.
+pub fn assert_schema<T: IntoDataFrame>(columns: &[(&str, DataType)]) {
+    let schema = T::schema();
+    let actual: Vec<_> = schema
+        .iter()
+        .map(|(name, dtype)| (name.as_str(), dtype.clone()))
+        .collect();
+    assert_eq!(actual, columns);
+}
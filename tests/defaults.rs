@@ -0,0 +1,109 @@
+use polars::prelude::*;
+use polars_derive::{FromColumn, FromDataFrame, IntoColumn, IntoDataFrame};
+
+use common::round_trip;
+
+mod common;
+
+fn default_region() -> String {
+    "us-east-1".to_owned()
+}
+
+fn default_tags() -> Option<Vec<String>> {
+    Some(vec!["untagged".to_owned()])
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Host {
+    name: String,
+    #[df(default)]
+    cores: u32,
+    #[df(default = default_region)]
+    region: String,
+    #[df(default = default_tags)]
+    tags: Option<Vec<String>>,
+}
+
+#[test]
+fn test_missing_columns() {
+    // an older file, written before the other columns were added
+    let df = df! { "name" => ["db-1", "db-2"] }.unwrap();
+
+    let list = Host::from_df(&df).unwrap();
+    let names: Vec<_> = list.iter().map(|host| host.name.as_str()).collect();
+    assert_eq!(names, ["db-1", "db-2"]);
+
+    for host in list {
+        assert_eq!(host.cores, 0);
+        assert_eq!(host.region, "us-east-1");
+        assert_eq!(host.tags, Some(vec!["untagged".to_owned()]));
+    }
+}
+
+#[test]
+fn test_null_values() {
+    let df = df! {
+        "name" => ["db-1", "db-2"],
+        "cores" => [Some(8u32), None],
+        "region" => [None, Some("eu-west-1")],
+    }
+    .unwrap();
+    let tags = Series::full_null("tags", 2, &DataType::List(Box::new(DataType::Utf8)));
+    let df = df.hstack(&[tags]).unwrap();
+
+    let list = Host::from_df(&df).unwrap();
+    assert_eq!(
+        list,
+        [
+            Host {
+                name: "db-1".to_owned(),
+                cores: 8,
+                region: "us-east-1".to_owned(),
+                // nulls are valid values for optional fields
+                tags: None,
+            },
+            Host {
+                name: "db-2".to_owned(),
+                cores: 0,
+                region: "eu-west-1".to_owned(),
+                tags: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_read_write() {
+    round_trip(&[Host {
+        name: "web-1".to_owned(),
+        cores: 4,
+        region: "ap-south-1".to_owned(),
+        tags: Some(vec!["web".to_owned()]),
+    }]);
+}
+
+#[test]
+fn test_nested_missing_column() {
+    let column = StructChunked::new("host", &[Series::new("name", ["db-1", "db-2"])])
+        .unwrap()
+        .into_series();
+
+    let list = Host::from_column(&column)
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[1].as_ref().unwrap().region, "us-east-1");
+
+    // a missing row is still read as None
+    let column = Host::into_column("host", vec![None]);
+    assert_eq!(
+        Host::from_column(&column)
+            .unwrap()
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        [None]
+    );
+}
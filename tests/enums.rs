@@ -0,0 +1,279 @@
+use polars::prelude::*;
+use polars_derive::{Error, FromColumn, FromDataFrame, IntoColumn, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, Copy, PartialEq, Debug)]
+enum Status {
+    Active,
+    #[df(rename = "on-hold")]
+    OnHold,
+    Closed,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, Copy, PartialEq, Debug)]
+#[df(rename_all = "snake_case")]
+enum Priority {
+    VeryLow,
+    Normal,
+    VeryHigh,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Ticket {
+    id: u32,
+    status: Status,
+    priority: Option<Priority>,
+}
+
+fn tickets() -> Vec<Ticket> {
+    vec![
+        Ticket {
+            id: 1,
+            status: Status::Active,
+            priority: Some(Priority::VeryHigh),
+        },
+        Ticket {
+            id: 2,
+            status: Status::OnHold,
+            priority: None,
+        },
+        Ticket {
+            id: 3,
+            status: Status::Closed,
+            priority: Some(Priority::VeryLow),
+        },
+    ]
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Ticket>(&[
+        ("id", DataType::UInt32),
+        ("status", DataType::Utf8),
+        ("priority", DataType::Utf8),
+    ]);
+}
+
+#[test]
+fn test_read_write() {
+    let df = round_trip(&tickets());
+
+    let status: Vec<_> = df
+        .column("status")
+        .unwrap()
+        .utf8()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(status, [Some("Active"), Some("on-hold"), Some("Closed")]);
+
+    let priority = df.column("priority").unwrap();
+    let priority: Vec<_> = priority.utf8().unwrap().into_iter().collect();
+    assert_eq!(priority, [Some("very_high"), None, Some("very_low")]);
+}
+
+#[test]
+fn test_read_categorical_as_utf8() {
+    let column = Status::into_column("status", vec![Some(Status::Closed), None])
+        .cast(&DataType::Categorical(None))
+        .unwrap();
+
+    let list = Status::from_column(&column)
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(list, [Some(Status::Closed), None]);
+}
+
+#[test]
+fn test_invalid_variant() {
+    let column = Series::new("status", ["Active", "Archived"]);
+
+    let list = Status::from_column(&column).unwrap();
+    assert!(list[0].is_ok());
+    assert!(matches!(list[1], Err(Error::UnknownVariant { row: 1, .. })));
+}
+
+#[cfg(feature = "dtype-categorical")]
+mod with_categorical {
+    use super::*;
+
+    #[derive(IntoDataFrame, FromDataFrame, Clone, Copy, PartialEq, Debug)]
+    #[df(dtype = Categorical, rename_all = "snake_case")]
+    enum Level {
+        Low,
+        High,
+    }
+
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    #[df(dtype = Categorical)]
+    enum Alert {
+        Raised { level: Level },
+        Cleared,
+    }
+
+    #[test]
+    fn test_categorical() {
+        assert_schema::<Alert>(&[
+            ("type", DataType::Categorical(None)),
+            ("level", DataType::Categorical(None)),
+        ]);
+
+        let df = round_trip(&[
+            Alert::Raised { level: Level::High },
+            Alert::Cleared,
+            Alert::Raised { level: Level::Low },
+        ]);
+
+        let tag = df.column("type").unwrap();
+        assert!(matches!(tag.dtype(), DataType::Categorical(_)));
+
+        let level = df.column("level").unwrap().cast(&DataType::Utf8).unwrap();
+        let level: Vec<_> = level.utf8().unwrap().into_iter().collect();
+        assert_eq!(level, [Some("high"), None, Some("low")]);
+    }
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(tag = "kind", rename_all = "snake_case")]
+enum LogEvent {
+    Login {
+        user: String,
+        ip: Option<String>,
+    },
+    Purchase {
+        user: String,
+        amount: f64,
+        items: Vec<u32>,
+    },
+    #[df(rename = "shutdown")]
+    SystemShutdown,
+}
+
+fn log_events() -> Vec<LogEvent> {
+    vec![
+        LogEvent::Login {
+            user: "alice".to_owned(),
+            ip: Some("10.0.0.1".to_owned()),
+        },
+        LogEvent::Purchase {
+            user: "bob".to_owned(),
+            amount: 12.5,
+            items: vec![1, 2],
+        },
+        LogEvent::SystemShutdown,
+        LogEvent::Login {
+            user: "carol".to_owned(),
+            ip: None,
+        },
+    ]
+}
+
+#[test]
+fn test_tagged_schema() {
+    assert_schema::<LogEvent>(&[
+        ("kind", DataType::Utf8),
+        ("user", DataType::Utf8),
+        ("ip", DataType::Utf8),
+        ("amount", DataType::Float64),
+        ("items", DataType::List(Box::new(DataType::UInt32))),
+    ]);
+}
+
+#[test]
+fn test_tagged_read_write() {
+    let df = round_trip(&log_events());
+
+    let kind: Vec<_> = df
+        .column("kind")
+        .unwrap()
+        .utf8()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(
+        kind,
+        [
+            Some("login"),
+            Some("purchase"),
+            Some("shutdown"),
+            Some("login")
+        ]
+    );
+
+    // columns which belong to other variants are null
+    let amount: Vec<_> = df
+        .column("amount")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(amount, [None, Some(12.5), None, None]);
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Session {
+    id: u32,
+    last_event: Option<LogEvent>,
+}
+
+#[test]
+fn test_tagged_nested() {
+    round_trip(&[
+        Session {
+            id: 1,
+            last_event: log_events().pop(),
+        },
+        Session {
+            id: 2,
+            last_event: None,
+        },
+    ]);
+}
+
+#[test]
+fn test_tagged_missing_field() {
+    let df = df! {
+        "kind" => ["purchase"],
+        "user" => ["bob"],
+        "ip" => [None::<&str>],
+        "amount" => [None::<f64>],
+        "items" => [Series::new("", [1u32])],
+    }
+    .unwrap();
+
+    // amount is required for purchases
+    assert!(LogEvent::from_df(&df).is_err());
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(tag = "state")]
+enum Task {
+    Queued {
+        id: u32,
+        #[df(skip_serializing, default)]
+        note: Option<String>,
+        priority: i32,
+    },
+    Done {
+        id: u32,
+    },
+}
+
+#[test]
+fn test_tagged_skip_serializing() {
+    let df = round_trip(&[
+        Task::Queued {
+            id: 1,
+            note: None,
+            priority: 2,
+        },
+        Task::Done { id: 2 },
+    ]);
+    assert_eq!(df.get_column_names(), ["state", "id", "priority"]);
+}
@@ -0,0 +1,205 @@
+use std::error::Error as _;
+
+use polars::prelude::*;
+use polars_derive::{Error, FromDataFrame, IntoDataFrame};
+use url::Url;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Visit {
+    id: u32,
+    #[df(into = String, try_from_borrow)]
+    page: Url,
+    referrer: Option<String>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+enum Action {
+    Click { x: u32, y: u32 },
+    Scroll { offset: u32 },
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Session {
+    user: String,
+    visit: Visit,
+    history: Vec<Visit>,
+}
+
+/// Reads the rows of `df`, returning the first error as an [`Error`].
+fn read_rows<T: FromDataFrame>(df: &DataFrame) -> Result<Vec<T>, Error> {
+    T::iter_df(df)?.collect()
+}
+
+fn visits() -> DataFrame {
+    df! {
+        "id" => [Some(1u32), Some(2), None, Some(4)],
+        "page" => ["https://a.example", "https://b.example", "https://c.example", "nope"],
+        "referrer" => [None, Some("https://a.example"), None, None],
+    }
+    .unwrap()
+}
+
+#[test]
+fn test_missing_column() {
+    let df = visits().drop("page").unwrap();
+
+    match read_rows::<Visit>(&df) {
+        Err(Error::MissingColumn { column }) => assert_eq!(column, "page"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_dtype_mismatch() {
+    let mut df = visits();
+    df.with_column(Series::new("id", ["1", "2", "3", "4"]))
+        .unwrap();
+
+    match Visit::iter_df(&df).map(|_| ()) {
+        Err(Error::DtypeMismatch {
+            column,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(column, "id");
+            assert_eq!(expected, DataType::UInt32);
+            assert_eq!(actual, DataType::Utf8);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_missing_value() {
+    let rows: Vec<_> = Visit::iter_df(&visits()).unwrap().collect();
+
+    assert!(rows[0].is_ok());
+    assert!(rows[1].is_ok());
+    match &rows[2] {
+        Err(Error::MissingValue { column, row }) => {
+            assert_eq!(column, "id");
+            assert_eq!(*row, 2);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_invalid_value() {
+    let df = visits().slice(3, 1);
+
+    match read_rows::<Visit>(&df) {
+        Err(err @ Error::InvalidValue { .. }) => {
+            let source = err.source().unwrap();
+            assert!(source.downcast_ref::<url::ParseError>().is_some());
+            assert!(err.to_string().contains("page"), "{err}");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_unknown_variant() {
+    let df = df! {
+        "type" => ["Click", "Scroll", "Hover"],
+        "x" => [Some(1u32), None, None],
+        "y" => [Some(2u32), None, None],
+        "offset" => [None, Some(3u32), None],
+    }
+    .unwrap();
+
+    match read_rows::<Action>(&df) {
+        Err(Error::UnknownVariant {
+            column,
+            row,
+            variant,
+        }) => {
+            assert_eq!(column, "type");
+            assert_eq!(row, 2);
+            assert_eq!(variant, "Hover");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_into_polars_error() {
+    // the errors of from_df are converted, so that its signature stays the same
+    fn read(df: &DataFrame) -> PolarsResult<Vec<Visit>> {
+        Visit::from_df(df)
+    }
+
+    let err = read(&visits()).unwrap_err();
+    assert!(matches!(err, PolarsError::NoData(_)), "{err}");
+    assert!(err.to_string().contains("row 2"), "{err}");
+
+    let err = read(&visits().drop("id").unwrap()).unwrap_err();
+    assert!(matches!(err, PolarsError::ColumnNotFound(_)), "{err}");
+}
+
+fn sessions(pages: [&str; 2]) -> DataFrame {
+    let visit = StructChunked::new(
+        "visit",
+        &[
+            Series::new("id", [1u32, 2]),
+            Series::new("page", pages),
+            Series::new("referrer", [None::<&str>, None]),
+        ],
+    )
+    .unwrap()
+    .into_series();
+    let mut history = visit.clone();
+    history.rename("history");
+    let history = history.reshape(&[2, 1]).unwrap();
+
+    DataFrame::new(vec![Series::new("user", ["alice", "bob"]), visit, history]).unwrap()
+}
+
+#[test]
+fn test_nested_error() {
+    let df = sessions(["https://a.example", "nope"]);
+    let rows: Vec<_> = Session::iter_df(&df).unwrap().collect();
+
+    assert!(rows[0].is_ok());
+    match &rows[1] {
+        Err(err @ Error::InvalidValue { column, row, .. }) => {
+            assert_eq!(column, "visit.page");
+            assert_eq!(*row, 1);
+            let source = err.source().unwrap();
+            assert!(source.downcast_ref::<url::ParseError>().is_some());
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_nested_list_error() {
+    let mut df = sessions(["https://a.example", "nope"]);
+    df.replace("visit", sessions(["https://a.example"; 2])["visit"].clone())
+        .unwrap();
+
+    match read_rows::<Session>(&df) {
+        Err(Error::InvalidValue { column, row, .. }) => {
+            assert_eq!(column, "history.<item>.page");
+            assert_eq!(row, 1);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_nested_missing_column() {
+    let mut df = sessions(["https://a.example"; 2]);
+    df.replace(
+        "visit",
+        StructChunked::new("visit", &[Series::new("id", [1u32, 2])])
+            .unwrap()
+            .into_series(),
+    )
+    .unwrap();
+
+    match read_rows::<Session>(&df) {
+        Err(Error::MissingColumn { column }) => assert_eq!(column, "visit.page"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
@@ -0,0 +1,167 @@
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+use url::Url;
+
+use common::assert_schema;
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Link {
+    #[df(dtype = Utf8, try_serialize_with_borrow = normalize_url)]
+    url: String,
+
+    #[df(try_into = u32, try_from)]
+    clicks: u64,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Page {
+    title: String,
+    link: Link,
+}
+
+fn normalize_url(url: &str) -> Result<String, url::ParseError> {
+    Ok(Url::parse(url)?.to_string())
+}
+
+fn link(url: &str, clicks: u64) -> Link {
+    Link {
+        url: url.to_owned(),
+        clicks,
+    }
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Link>(&[("url", DataType::Utf8), ("clicks", DataType::UInt32)]);
+}
+
+#[test]
+fn test_into_df() {
+    let links = vec![
+        link("https://EXAMPLE.com", 1),
+        link("https://example.org/a", 2),
+    ];
+    let df = Link::into_df(links.into_iter()).unwrap();
+
+    let url = df.column("url").unwrap().utf8().unwrap();
+    assert_eq!(url.get(0), Some("https://example.com/"));
+    assert_eq!(url.get(1), Some("https://example.org/a"));
+
+    let clicks = df.column("clicks").unwrap().u32().unwrap();
+    assert_eq!(clicks.get(1), Some(2));
+}
+
+#[test]
+fn test_serialize_error() {
+    let links = vec![link("https://example.com", 1), link("not a url", 2)];
+    let err = Link::into_df(links.into_iter()).unwrap_err().to_string();
+
+    assert!(err.contains("url"), "{err}");
+    assert!(err.contains("row 1"), "{err}");
+}
+
+#[test]
+fn test_try_into_error() {
+    let links = vec![
+        link("https://example.com", 1),
+        link("https://example.com", 2),
+        link("https://example.com", u64::MAX),
+    ];
+    let err = Link::to_df(&links).unwrap_err().to_string();
+
+    assert!(err.contains("clicks"), "{err}");
+    assert!(err.contains("row 2"), "{err}");
+}
+
+#[test]
+fn test_into_df_error() {
+    let err = Link::into_df([link("not a url", 1)].into_iter()).unwrap_err();
+    assert!(err.to_string().contains("row 0"), "{err}");
+
+    let err = Link::to_df(&[link("not a url", 1)]).unwrap_err();
+    assert!(err.to_string().contains("row 0"), "{err}");
+}
+
+#[test]
+#[should_panic(expected = "row 0")]
+fn test_into_series_panics() {
+    let _ = Link::into_series([link("not a url", 1)].into_iter());
+}
+
+#[test]
+fn test_nested_error() {
+    let pages = vec![Page {
+        title: "home".to_owned(),
+        link: link("not a url", 1),
+    }];
+    let err = Page::to_df(&pages).unwrap_err().to_string();
+    assert!(err.contains("url"), "{err}");
+
+    let pages = vec![Page {
+        title: "home".to_owned(),
+        link: link("https://example.com", 1),
+    }];
+    let df = Page::into_df(pages.into_iter()).unwrap();
+    let link = df.column("link").unwrap().struct_().unwrap();
+    let url = link.field_by_name("url").unwrap();
+    assert_eq!(url.utf8().unwrap().get(0), Some("https://example.com/"));
+}
+
+#[derive(IntoDataFrame, Clone, PartialEq, Debug)]
+enum Event {
+    Click {
+        #[df(try_into = u32)]
+        count: u64,
+    },
+    View {
+        #[df(try_into = u32)]
+        count: u64,
+    },
+}
+
+#[test]
+fn test_shared_fallible_column() {
+    let events = vec![Event::Click { count: 1 }, Event::View { count: u64::MAX }];
+    let err = Event::to_df(&events).unwrap_err().to_string();
+
+    assert!(err.contains("count"), "{err}");
+    assert!(err.contains("row 1"), "{err}");
+}
+
+/// A hand-written impl, which only has to provide the infallible methods.
+struct Counter(u32);
+
+impl IntoDataFrame for Counter {
+    fn schema() -> Schema {
+        Schema::from([Field::new("count", DataType::UInt32)].into_iter())
+    }
+
+    fn into_series(rows: impl Iterator<Item = Self>) -> Vec<Series> {
+        vec![Series::new(
+            "count",
+            rows.map(|row| row.0).collect::<Vec<_>>(),
+        )]
+    }
+
+    fn to_series<'a>(rows: impl Iterator<Item = &'a Self>) -> Vec<Series>
+    where
+        Self: 'a,
+    {
+        vec![Series::new(
+            "count",
+            rows.map(|row| row.0).collect::<Vec<_>>(),
+        )]
+    }
+}
+
+#[test]
+fn test_default_try_methods() {
+    let counters = vec![Counter(1), Counter(2)];
+    let df = Counter::to_df(&counters).unwrap();
+    assert_eq!(df.schema(), Counter::schema());
+
+    let df = Counter::into_df(counters.into_iter()).unwrap();
+    assert_eq!(df.column("count").unwrap().u32().unwrap().get(1), Some(2));
+}
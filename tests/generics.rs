@@ -0,0 +1,105 @@
+use std::fmt::Debug;
+
+use polars::prelude::*;
+use polars_derive::{FromColumn, FromDataFrame, IntoColumn, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Measurement<T> {
+    sensor: String,
+    value: T,
+    previous: Option<T>,
+}
+
+fn check_measurements<T>(items: Vec<Measurement<T>>, dtype: DataType)
+where
+    T: IntoColumn + FromColumn + Clone + PartialEq + Debug,
+{
+    assert_schema::<Measurement<T>>(&[
+        ("sensor", DataType::Utf8),
+        ("value", dtype.clone()),
+        ("previous", dtype),
+    ]);
+    round_trip(&items);
+}
+
+#[test]
+fn test_primitives() {
+    check_measurements(
+        vec![
+            Measurement {
+                sensor: "a".to_owned(),
+                value: 1.5f32,
+                previous: None,
+            },
+            Measurement {
+                sensor: "b".to_owned(),
+                value: 2.5f32,
+                previous: Some(1.5),
+            },
+        ],
+        DataType::Float32,
+    );
+
+    check_measurements(
+        vec![Measurement {
+            sensor: "a".to_owned(),
+            value: 3.25f64,
+            previous: Some(0.0),
+        }],
+        DataType::Float64,
+    );
+
+    check_measurements(
+        vec![Measurement {
+            sensor: "a".to_owned(),
+            value: -7i64,
+            previous: None,
+        }],
+        DataType::Int64,
+    );
+
+    check_measurements(
+        vec![Measurement {
+            sensor: "a".to_owned(),
+            value: "high".to_owned(),
+            previous: Some("low".to_owned()),
+        }],
+        DataType::Utf8,
+    );
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn test_nested() {
+    round_trip(&[Measurement {
+        sensor: "gps".to_owned(),
+        value: Point { x: 1, y: 2 },
+        previous: None,
+    }]);
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(bound = "T: IntoColumn + FromColumn + Default")]
+struct Reading<T> {
+    value: T,
+    #[df(skip)]
+    cached: T,
+}
+
+#[test]
+fn test_explicit_bound() {
+    let df = round_trip(&[Reading {
+        value: 4u32,
+        cached: 0,
+    }]);
+    assert_eq!(df.get_column_names(), ["value"]);
+}
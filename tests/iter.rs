@@ -0,0 +1,118 @@
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+enum Alert {
+    High { sensor: String, value: f64 },
+    Offline { sensor: String },
+}
+
+#[derive(FromDataFrame, PartialEq, Debug)]
+struct Empty {}
+
+#[test]
+fn test_iter() {
+    let df = df! {
+        "sensor" => ["a", "b", "c"],
+        "value" => [1.0, 2.0, 3.0],
+    }
+    .unwrap();
+
+    let mut rows = Reading::iter_df(&df).unwrap();
+    assert_eq!(
+        rows.next().unwrap().unwrap(),
+        Reading {
+            sensor: "a".to_owned(),
+            value: 1.0,
+        }
+    );
+
+    let rest: Vec<_> = rows.collect::<Result<_, polars_derive::Error>>().unwrap();
+    assert_eq!(rest.len(), 2);
+    assert_eq!(rest[1].sensor, "c");
+}
+
+#[test]
+fn test_iter_errors() {
+    let df = df! {
+        "sensor" => [Some("a"), None, Some("c")],
+        "value" => [1.0, 2.0, 3.0],
+    }
+    .unwrap();
+
+    // only the row with missing data fails
+    let rows: Vec<_> = Reading::iter_df(&df).unwrap().collect();
+    assert!(rows[0].is_ok());
+    assert!(rows[1].is_err());
+    assert!(rows[2].is_ok());
+
+    // missing columns are reported before any rows are read
+    let df = df! { "sensor" => ["a"] }.unwrap();
+    assert!(Reading::iter_df(&df).is_err());
+}
+
+#[test]
+fn test_iter_enum() {
+    let alerts = vec![
+        Alert::High {
+            sensor: "a".to_owned(),
+            value: 9.5,
+        },
+        Alert::Offline {
+            sensor: "b".to_owned(),
+        },
+    ];
+
+    let df = Alert::into_df(alerts.clone().into_iter()).unwrap();
+    let list = Alert::iter_df(&df)
+        .unwrap()
+        .collect::<Result<Vec<_>, polars_derive::Error>>()
+        .unwrap();
+    assert_eq!(list, alerts);
+}
+
+#[test]
+fn test_iter_no_columns() {
+    let df = df! { "other" => [1, 2] }.unwrap();
+
+    let rows: Vec<_> = Empty::iter_df(&df).unwrap().collect();
+    assert_eq!(rows.len(), 2);
+}
+
+/// A structure read through a manual impl, written before `iter_df` existed.
+#[derive(PartialEq, Debug)]
+struct Count(u32);
+
+impl FromDataFrame for Count {
+    fn from_df(df: &DataFrame) -> PolarsResult<Vec<Self>> {
+        Ok(df
+            .column("count")?
+            .u32()?
+            .into_no_null_iter()
+            .map(Count)
+            .collect())
+    }
+}
+
+#[test]
+fn test_iter_manual_impl() {
+    let df = df! { "count" => [1u32, 2] }.unwrap();
+
+    let rows = Count::iter_df(&df)
+        .unwrap()
+        .collect::<Result<Vec<_>, polars_derive::Error>>()
+        .unwrap();
+    assert_eq!(rows, [Count(1), Count(2)]);
+
+    let df = df! { "other" => [1u32] }.unwrap();
+    assert!(matches!(
+        Count::from_df_lenient(&df),
+        Err(polars_derive::Error::Polars(_))
+    ));
+}
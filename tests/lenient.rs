@@ -0,0 +1,198 @@
+use std::error::Error as _;
+
+use polars::prelude::*;
+use polars_derive::{Error, FromDataFrame, FromDataFrameRef, IntoDataFrame};
+use url::Url;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Link {
+    id: u32,
+    #[df(into = String, try_from_borrow)]
+    url: Url,
+}
+
+#[derive(FromDataFrameRef, PartialEq, Debug)]
+struct LinkRef<'a> {
+    id: u32,
+    url: &'a str,
+}
+
+fn links() -> DataFrame {
+    df! {
+        "id" => [Some(1u32), Some(2), None, Some(4)],
+        "url" => ["https://a.example", "not a url", "https://c.example", "https://d.example"],
+    }
+    .unwrap()
+}
+
+#[test]
+fn test_skips_bad_rows() {
+    let (rows, errors) = Link::from_df_lenient(&links()).unwrap();
+
+    let ids: Vec<_> = rows.iter().map(|link| link.id).collect();
+    assert_eq!(ids, [1, 4]);
+    assert_eq!(rows[1].url, Url::parse("https://d.example").unwrap());
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].row, 1);
+    assert!(matches!(errors[0].error, Error::InvalidValue { .. }));
+    assert_eq!(errors[1].row, 2);
+    assert!(matches!(errors[1].error, Error::MissingValue { .. }));
+    assert!(errors[1].source().is_some());
+}
+
+#[test]
+fn test_borrowed() {
+    let df = links();
+    let (rows, errors) = LinkRef::from_df_ref_lenient(&df).unwrap();
+
+    let urls: Vec<_> = rows.iter().map(|link| link.url).collect();
+    assert_eq!(
+        urls,
+        ["https://a.example", "not a url", "https://d.example"]
+    );
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].row, 2);
+}
+
+#[test]
+fn test_missing_column() {
+    let df = links().drop("url").unwrap();
+
+    assert!(matches!(
+        Link::from_df_lenient(&df),
+        Err(Error::MissingColumn { .. })
+    ));
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Post {
+    title: String,
+    link: Link,
+    related: Vec<Link>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Share {
+    user: String,
+    #[df(flatten, prefix = "link_")]
+    link: Link,
+}
+
+#[test]
+fn test_bad_nested_value() {
+    let link = StructChunked::new("link", links().get_columns())
+        .unwrap()
+        .into_series();
+    let mut related = link.clone();
+    related.rename("related");
+    let related = related.reshape(&[4, 1]).unwrap();
+    let df = DataFrame::new(vec![
+        Series::new("title", ["a", "b", "c", "d"]),
+        link,
+        related,
+    ])
+    .unwrap();
+
+    let (rows, errors) = Post::from_df_lenient(&df).unwrap();
+
+    let titles: Vec<_> = rows.iter().map(|post| post.title.as_str()).collect();
+    assert_eq!(titles, ["a", "d"]);
+    assert_eq!(rows[1].related, [rows[1].link.clone()]);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].row, 1);
+    match &errors[0].error {
+        Error::InvalidValue { column, row, .. } => {
+            assert_eq!(column, "link.url");
+            assert_eq!(*row, 1);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert_eq!(errors[1].row, 2);
+    assert!(matches!(errors[1].error, Error::MissingValue { .. }));
+}
+
+#[test]
+fn test_bad_flattened_value() {
+    let mut df = links();
+    df.rename("id", "link_id").unwrap();
+    df.rename("url", "link_url").unwrap();
+    df.with_column(Series::new("user", ["a", "b", "c", "d"]))
+        .unwrap();
+
+    let (rows, errors) = Share::from_df_lenient(&df).unwrap();
+
+    let users: Vec<_> = rows.iter().map(|share| share.user.as_str()).collect();
+    assert_eq!(users, ["a", "d"]);
+
+    assert_eq!(errors.len(), 2);
+    match &errors[0].error {
+        Error::InvalidValue { column, row, .. } => {
+            assert_eq!(column, "link_url");
+            assert_eq!(*row, 1);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    match &errors[1].error {
+        Error::MissingValue { column, row } => {
+            assert_eq!(column, "link_id");
+            assert_eq!(*row, 2);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[derive(FromDataFrame, PartialEq, Debug)]
+struct Edge {
+    id: u32,
+    nodes: (u32, u32),
+}
+
+#[test]
+fn test_bad_tuple_element() {
+    let nodes = StructChunked::new(
+        "nodes",
+        &[
+            Series::new("0", [Some(1u32), None, Some(5)]),
+            Series::new("1", [2u32, 4, 6]),
+        ],
+    )
+    .unwrap()
+    .into_series();
+    let df = DataFrame::new(vec![Series::new("id", [1u32, 2, 3]), nodes]).unwrap();
+
+    let (rows, errors) = Edge::from_df_lenient(&df).unwrap();
+
+    assert_eq!(
+        rows,
+        [
+            Edge {
+                id: 1,
+                nodes: (1, 2)
+            },
+            Edge {
+                id: 3,
+                nodes: (5, 6)
+            }
+        ]
+    );
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].row, 1);
+    match &errors[0].error {
+        Error::MissingValue { column, row } => {
+            assert_eq!(column, "nodes.0");
+            assert_eq!(*row, 1);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+
+    let nodes = StructChunked::new("nodes", &[Series::new("0", [1u32])])
+        .unwrap()
+        .into_series();
+    let df = DataFrame::new(vec![Series::new("id", [1u32]), nodes]).unwrap();
+    match Edge::from_df_lenient(&df) {
+        Err(Error::MissingColumn { column }) => assert_eq!(column, "nodes.1"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
@@ -0,0 +1,314 @@
+use std::io::Cursor;
+
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoColumn, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Address {
+    street: String,
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct GeoPoint {
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Event {
+    id: u64,
+    address: Address,
+    location: Option<GeoPoint>,
+}
+
+fn events() -> Vec<Event> {
+    vec![
+        Event {
+            id: 1,
+            address: Address {
+                street: "1 Main St".to_owned(),
+                city: "Springfield".to_owned(),
+                zip: Some("12345".to_owned()),
+            },
+            location: Some(GeoPoint {
+                lat: 39.8,
+                lon: -89.6,
+            }),
+        },
+        Event {
+            id: 2,
+            address: Address {
+                street: "2 Elm St".to_owned(),
+                city: "Shelbyville".to_owned(),
+                zip: None,
+            },
+            location: None,
+        },
+    ]
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Event>(&[
+        ("id", DataType::UInt64),
+        (
+            "address",
+            DataType::Struct(vec![
+                Field::new("street", DataType::Utf8),
+                Field::new("city", DataType::Utf8),
+                Field::new("zip", DataType::Utf8),
+            ]),
+        ),
+        (
+            "location",
+            DataType::Struct(vec![
+                Field::new("lat", DataType::Float64),
+                Field::new("lon", DataType::Float64),
+            ]),
+        ),
+    ]);
+}
+
+#[test]
+fn test_read_write() {
+    let df = round_trip(&events());
+    assert_eq!(df.shape(), (2, 3));
+
+    let address = df.column("address").unwrap().struct_().unwrap();
+    let city = address.field_by_name("city").unwrap();
+    let city: Vec<_> = city.utf8().unwrap().into_iter().collect();
+    assert_eq!(city, [Some("Springfield"), Some("Shelbyville")]);
+
+    let zip = address.field_by_name("zip").unwrap();
+    let zip: Vec<_> = zip.utf8().unwrap().into_iter().collect();
+    assert_eq!(zip, [Some("12345"), None]);
+
+    // a missing struct is stored as nulls in all of its fields
+    let location = df.column("location").unwrap().struct_().unwrap();
+    let lat = location.field_by_name("lat").unwrap();
+    let lat: Vec<_> = lat.f64().unwrap().into_iter().collect();
+    assert_eq!(lat, [Some(39.8), None]);
+}
+
+#[test]
+fn test_read_write_parquet() {
+    let mut tmpfile: Vec<u8> = vec![];
+    let items = events();
+
+    {
+        let mut df = Event::into_df(items.clone().into_iter()).unwrap();
+        ParquetWriter::new(Cursor::new(&mut tmpfile))
+            .finish(&mut df)
+            .unwrap();
+    }
+
+    let df = ParquetReader::new(Cursor::new(&mut tmpfile))
+        .finish()
+        .unwrap();
+    let list = Event::from_df(&df).unwrap();
+
+    assert_eq!(items, list);
+}
+
+#[test]
+fn test_missing_nested_field() {
+    let address = StructChunked::new("address", &[Series::new("street", ["1 Main St"])])
+        .unwrap()
+        .into_series();
+
+    let location = GeoPoint::into_column("location", vec![None]);
+
+    let df = DataFrame::new(vec![Series::new("id", [1u64]), address, location]).unwrap();
+
+    // the city column is missing from the address struct
+    assert!(Event::from_df(&df).is_err());
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Segment {
+    start: (f64, f64),
+    end: Option<(f64, f64)>,
+    label: (String, Vec<u32>),
+    path: Vec<(f64, f64)>,
+}
+
+fn segments() -> Vec<Segment> {
+    vec![
+        Segment {
+            start: (0.0, 1.0),
+            end: Some((2.0, 3.0)),
+            label: ("a".to_owned(), vec![1, 2]),
+            path: vec![(0.5, 1.5), (1.0, 2.0)],
+        },
+        Segment {
+            start: (4.0, 5.0),
+            end: None,
+            label: ("b".to_owned(), vec![]),
+            path: vec![],
+        },
+    ]
+}
+
+#[test]
+fn test_tuple_schema() {
+    let point = DataType::Struct(vec![
+        Field::new("0", DataType::Float64),
+        Field::new("1", DataType::Float64),
+    ]);
+    assert_schema::<Segment>(&[
+        ("start", point.clone()),
+        ("end", point.clone()),
+        (
+            "label",
+            DataType::Struct(vec![
+                Field::new("0", DataType::Utf8),
+                Field::new("1", DataType::List(Box::new(DataType::UInt32))),
+            ]),
+        ),
+        ("path", DataType::List(Box::new(point))),
+    ]);
+}
+
+#[test]
+fn test_tuple_read_write() {
+    round_trip(&segments());
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Measurement {
+    pair: (Option<i32>, Option<String>),
+    maybe: Option<(Option<i32>, Option<String>)>,
+}
+
+#[test]
+fn test_tuple_elements_all_none() {
+    round_trip(&[
+        Measurement {
+            pair: (None, None),
+            maybe: None,
+        },
+        Measurement {
+            pair: (Some(1), None),
+            maybe: Some((None, Some("a".to_owned()))),
+        },
+    ]);
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Audit {
+    created_at: i64,
+    created_by: String,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Invoice {
+    id: u64,
+    #[df(flatten)]
+    audit: Audit,
+    #[df(flatten, prefix = "billing_")]
+    billing: Address,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Customer {
+    name: String,
+    last_invoice: Option<Invoice>,
+}
+
+fn invoices() -> Vec<Invoice> {
+    vec![Invoice {
+        id: 7,
+        audit: Audit {
+            created_at: 1_700_000_000,
+            created_by: "admin".to_owned(),
+        },
+        billing: Address {
+            street: "1 Main St".to_owned(),
+            city: "Springfield".to_owned(),
+            zip: None,
+        },
+    }]
+}
+
+#[test]
+fn test_flatten_schema() {
+    assert_schema::<Invoice>(&[
+        ("id", DataType::UInt64),
+        ("created_at", DataType::Int64),
+        ("created_by", DataType::Utf8),
+        ("billing_street", DataType::Utf8),
+        ("billing_city", DataType::Utf8),
+        ("billing_zip", DataType::Utf8),
+    ]);
+}
+
+#[test]
+fn test_flatten_read_write() {
+    round_trip(&invoices());
+}
+
+#[test]
+fn test_flatten_inside_nested() {
+    round_trip(&[
+        Customer {
+            name: "Alice".to_owned(),
+            last_invoice: invoices().pop(),
+        },
+        Customer {
+            name: "Bob".to_owned(),
+            last_invoice: None,
+        },
+    ]);
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Contact {
+    phone: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Person {
+    name: String,
+    contact: Contact,
+    backup: Option<Contact>,
+    history: Vec<Contact>,
+}
+
+#[test]
+fn test_nested_fields_all_none() {
+    let empty = Contact {
+        phone: None,
+        email: None,
+    };
+    let items = vec![
+        Person {
+            name: "Alice".to_owned(),
+            contact: empty.clone(),
+            backup: None,
+            history: vec![empty.clone()],
+        },
+        Person {
+            name: "Bob".to_owned(),
+            contact: Contact {
+                phone: Some("555".to_owned()),
+                email: None,
+            },
+            backup: Some(Contact {
+                phone: None,
+                email: Some("bob@example.com".to_owned()),
+            }),
+            history: vec![],
+        },
+    ];
+
+    // a structure whose fields are all None is stored in the same way as a
+    // missing one, but it can't be missing unless the field is an Option
+    round_trip(&items);
+}
@@ -0,0 +1,145 @@
+extern crate alloc;
+
+use polars::prelude::*;
+use polars_derive::{Error, FromDataFrame, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Sensor {
+    readings: Vec<Option<f64>>,
+    labels: Option<Vec<Option<String>>>,
+    grid: Vec<Vec<Option<i32>>>,
+    batches: Vec<Option<Vec<u32>>>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Strict {
+    readings: Vec<f64>,
+}
+
+/// Same as Sensor, but with the lists and options named through their modules.
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct QualifiedSensor {
+    readings: std::vec::Vec<std::option::Option<f64>>,
+    labels: core::option::Option<std::vec::Vec<Option<String>>>,
+    grid: Vec<alloc::vec::Vec<core::option::Option<i32>>>,
+    batches: std::vec::Vec<Option<Vec<u32>>>,
+}
+
+/// The lists are stored through IntoColumn instead of by the derive.
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Generic<T> {
+    values: Vec<Option<T>>,
+}
+
+#[derive(FromDataFrame, Clone, PartialEq, Debug)]
+struct StrictGeneric<T> {
+    values: Vec<T>,
+}
+
+fn sensor(readings: Vec<Option<f64>>, labels: Option<Vec<Option<String>>>) -> Sensor {
+    Sensor {
+        readings,
+        labels,
+        grid: vec![vec![None]],
+        batches: vec![None],
+    }
+}
+
+#[test]
+fn test_schema() {
+    let list = |dtype| DataType::List(Box::new(dtype));
+
+    assert_schema::<Sensor>(&[
+        ("readings", list(DataType::Float64)),
+        ("labels", list(DataType::Utf8)),
+        ("grid", list(list(DataType::Int32))),
+        ("batches", list(list(DataType::UInt32))),
+    ]);
+}
+
+#[test]
+fn test_qualified_paths() {
+    assert_eq!(QualifiedSensor::schema(), Sensor::schema());
+
+    let rows = vec![QualifiedSensor {
+        readings: vec![Some(1.5), None],
+        labels: None,
+        grid: vec![vec![None, Some(2)]],
+        batches: vec![None, Some(vec![3])],
+    }];
+    let df = QualifiedSensor::to_df(&rows).unwrap();
+    assert_eq!(df.schema(), Sensor::schema());
+    assert_eq!(df.column("labels").unwrap().null_count(), 1);
+
+    assert_eq!(QualifiedSensor::from_df(&df).unwrap(), rows);
+}
+
+#[test]
+fn test_null_items() {
+    let sensors = vec![
+        sensor(
+            vec![Some(1.5), None],
+            Some(vec![None, Some("x".to_owned())]),
+        ),
+        sensor(vec![], None),
+    ];
+    let df = Sensor::to_df(&sensors).unwrap();
+
+    let readings = df.column("readings").unwrap().list().unwrap();
+    assert_eq!(readings.get(0).unwrap().null_count(), 1);
+    let labels = df.column("labels").unwrap();
+    assert_eq!(labels.null_count(), 1);
+
+    assert_eq!(Sensor::from_df(&df).unwrap(), sensors);
+}
+
+#[test]
+fn test_all_null_lists() {
+    // every list is missing, or only holds nulls, so Polars can't infer the
+    // data type of the items from them
+    let sensors = vec![sensor(vec![None], None), sensor(vec![None, None], None)];
+    let df = round_trip(&sensors);
+    assert_eq!(df.column("labels").unwrap().null_count(), 2);
+}
+
+#[test]
+fn test_all_null_lists_through_traits() {
+    round_trip(&[
+        Generic::<u32> { values: vec![None] },
+        Generic { values: vec![] },
+    ]);
+}
+
+#[test]
+fn test_null_item_in_strict_list() {
+    let sensors = vec![sensor(vec![Some(1.0)], None), sensor(vec![None], None)];
+    let df = Sensor::into_df(sensors.into_iter()).unwrap();
+
+    match Strict::iter_df(&df).and_then(Iterator::collect::<Result<Vec<_>, _>>) {
+        Err(Error::MissingValue { column, row }) => {
+            assert_eq!(column, "readings.<item>");
+            assert_eq!(row, 1);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_null_item_through_traits() {
+    let rows = vec![Generic::<u32> {
+        values: vec![Some(1), None],
+    }];
+    let df = Generic::to_df(&rows).unwrap();
+
+    match StrictGeneric::<u32>::iter_df(&df).and_then(Iterator::collect::<Result<Vec<_>, _>>) {
+        Err(Error::MissingValue { column, row }) => {
+            assert_eq!(column, "values.<item>");
+            assert_eq!(row, 0);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
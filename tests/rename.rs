@@ -0,0 +1,127 @@
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(rename_all = "camelCase")]
+struct Order {
+    order_id: u64,
+    customer_name: String,
+    #[df(rename = "TOTAL")]
+    total_amount: f64,
+    r#type: Option<String>,
+}
+
+fn orders() -> Vec<Order> {
+    vec![
+        Order {
+            order_id: 1,
+            customer_name: "Alice".to_owned(),
+            total_amount: 10.0,
+            r#type: Some("online".to_owned()),
+        },
+        Order {
+            order_id: 2,
+            customer_name: "Bob".to_owned(),
+            total_amount: 22.5,
+            r#type: None,
+        },
+    ]
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Order>(&[
+        ("orderId", DataType::UInt64),
+        ("customerName", DataType::Utf8),
+        ("TOTAL", DataType::Float64),
+        ("type", DataType::Utf8),
+    ]);
+}
+
+#[test]
+fn test_read_write() {
+    round_trip(&orders());
+}
+
+#[test]
+fn test_read_renamed() {
+    let mut df = df! {
+        "orderId" => [3u64],
+        "customerName" => ["Carol"],
+        "TOTAL" => [5.0],
+        "type" => [Some("store")],
+    }
+    .unwrap();
+
+    let list = Order::from_df(&df).unwrap();
+    assert_eq!(
+        list,
+        [Order {
+            order_id: 3,
+            customer_name: "Carol".to_owned(),
+            total_amount: 5.0,
+            r#type: Some("store".to_owned()),
+        }]
+    );
+
+    // the names of the fields are not used
+    df.rename("orderId", "order_id").unwrap();
+    assert!(Order::from_df(&df).is_err());
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(rename_all = "SCREAMING-KEBAB-CASE")]
+struct Metrics {
+    cpu_usage: f32,
+    mem_usage: f32,
+}
+
+#[test]
+fn test_non_identifier_names() {
+    let df = round_trip(&[Metrics {
+        cpu_usage: 0.5,
+        mem_usage: 0.25,
+    }]);
+    assert_eq!(df.get_column_names(), ["CPU-USAGE", "MEM-USAGE"]);
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Visit {
+    #[df(alias = "user", alias = "uid")]
+    user_id: String,
+    pages: u32,
+}
+
+#[test]
+fn test_alias() {
+    let expected = vec![Visit {
+        user_id: "alice".to_owned(),
+        pages: 3,
+    }];
+
+    // columns are written under their actual names
+    let df = round_trip(&expected);
+    assert_eq!(df.get_column_names(), ["user_id", "pages"]);
+
+    let df = df! { "user" => ["alice"], "pages" => [3u32] }.unwrap();
+    assert_eq!(Visit::from_df(&df).unwrap(), expected);
+
+    let df = df! { "uid" => ["alice"], "pages" => [3u32] }.unwrap();
+    assert_eq!(Visit::from_df(&df).unwrap(), expected);
+
+    // the actual name takes precedence, then the aliases in order
+    let df = df! {
+        "uid" => ["bob"],
+        "user" => ["alice"],
+        "pages" => [3u32],
+    }
+    .unwrap();
+    assert_eq!(Visit::from_df(&df).unwrap(), expected);
+
+    let df = df! { "pages" => [3u32] }.unwrap();
+    assert!(Visit::from_df(&df).is_err());
+}
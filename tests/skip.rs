@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+
+use common::assert_schema;
+
+mod common;
+
+fn default_retries() -> u32 {
+    3
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, Debug)]
+struct Job {
+    id: u64,
+    name: String,
+    #[df(skip)]
+    handle: Arc<Mutex<Vec<String>>>,
+    #[df(skip_serializing)]
+    note: Option<String>,
+    #[df(skip_deserializing, default = default_retries)]
+    retries: u32,
+}
+
+fn jobs() -> Vec<Job> {
+    vec![
+        Job {
+            id: 1,
+            name: "build".to_owned(),
+            handle: Arc::new(Mutex::new(vec!["started".to_owned()])),
+            note: Some("unused".to_owned()),
+            retries: 0,
+        },
+        Job {
+            id: 2,
+            name: "test".to_owned(),
+            handle: Default::default(),
+            note: None,
+            retries: 1,
+        },
+    ]
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Job>(&[
+        ("id", DataType::UInt64),
+        ("name", DataType::Utf8),
+        ("retries", DataType::UInt32),
+    ]);
+}
+
+#[test]
+fn test_write() {
+    let df = Job::into_df(jobs().into_iter()).unwrap();
+
+    assert_eq!(df.get_column_names(), ["id", "name", "retries"]);
+    assert_eq!(df.schema(), Job::schema());
+}
+
+#[test]
+fn test_read() {
+    let df = df! {
+        "id" => [1u64],
+        "name" => ["build"],
+        "note" => ["hello"],
+        "retries" => [7u32],
+    }
+    .unwrap();
+
+    let list = Job::from_df(&df).unwrap();
+    assert_eq!(list.len(), 1);
+
+    let job = &list[0];
+    assert_eq!(job.id, 1);
+    assert_eq!(job.name, "build");
+    assert!(job.handle.lock().unwrap().is_empty());
+    assert_eq!(job.note.as_deref(), Some("hello"));
+    // the column is ignored b/c the field is skipped when reading
+    assert_eq!(job.retries, 3);
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Cached {
+    #[df(skip)]
+    value: Option<u32>,
+}
+
+#[test]
+fn test_read_no_columns() {
+    let df = df! { "other" => [1, 2, 3] }.unwrap();
+
+    let list = Cached::from_df(&df).unwrap();
+    assert_eq!(list, vec![Cached { value: None }; 3]);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+#[cfg(feature = "std-time")]
+mod with_std_time {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use polars::prelude::*;
+    use polars_derive::{Error, FromDataFrame, IntoDataFrame};
+
+    use crate::common::assert_schema;
+
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    struct Request {
+        sent: std::time::SystemTime,
+        #[df(time_unit = "ns")]
+        received: Option<std::time::SystemTime>,
+        elapsed: core::time::Duration,
+        #[df(time_unit = "ns")]
+        retries: Vec<Duration>,
+    }
+
+    /// The fields are read through FromColumn instead of by the derive.
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    struct Generic<T> {
+        value: T,
+    }
+
+    fn request(sent: SystemTime) -> Request {
+        Request {
+            sent,
+            received: None,
+            elapsed: Duration::ZERO,
+            retries: vec![],
+        }
+    }
+
+    #[test]
+    fn test_schema() {
+        assert_schema::<Request>(&[
+            ("sent", DataType::Datetime(TimeUnit::Milliseconds, None)),
+            ("received", DataType::Datetime(TimeUnit::Nanoseconds, None)),
+            ("elapsed", DataType::Duration(TimeUnit::Milliseconds)),
+            (
+                "retries",
+                DataType::List(Box::new(DataType::Duration(TimeUnit::Nanoseconds))),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_before_epoch() {
+        // timestamps are rounded down, like chrono does
+        let sent = UNIX_EPOCH - Duration::from_micros(1_500);
+        let df = Request::to_df(&[request(sent)]).unwrap();
+
+        let timestamps = df.column("sent").unwrap().datetime().unwrap();
+        assert_eq!(timestamps.get(0), Some(-2));
+
+        let read = Request::from_df(&df).unwrap();
+        assert_eq!(read[0].sent, UNIX_EPOCH - Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        // about 292 years fit in 64 bits of nanoseconds
+        let far = UNIX_EPOCH + Duration::from_secs(300 * 365 * 86_400);
+        let rows = [
+            request(UNIX_EPOCH),
+            Request {
+                received: Some(far),
+                ..request(far)
+            },
+        ];
+        let err = Request::to_df(&rows).unwrap_err();
+        assert!(err.to_string().contains("field received in row 1"), "{err}");
+
+        let rows = [Request {
+            retries: vec![Duration::ZERO, Duration::MAX],
+            ..request(UNIX_EPOCH)
+        }];
+        let err = Request::to_df(&rows).unwrap_err();
+        assert!(err.to_string().contains("field retries in row 0"), "{err}");
+
+        let rows = [Generic {
+            value: Duration::MAX,
+        }];
+        let err = Generic::to_df(&rows).unwrap_err();
+        assert!(err.to_string().contains("field value in row 0"), "{err}");
+    }
+
+    #[test]
+    fn test_read_column_time_unit_through_traits() {
+        let value = Int64Chunked::new("value", [-1_500i64])
+            .into_datetime(TimeUnit::Microseconds, None)
+            .into_series();
+        let df = DataFrame::new(vec![value]).unwrap();
+
+        assert_eq!(
+            Generic::<SystemTime>::from_df(&df).unwrap(),
+            [Generic {
+                value: UNIX_EPOCH - Duration::from_micros(1_500)
+            }]
+        );
+    }
+
+    #[derive(FromDataFrame, Debug)]
+    struct Elapsed {
+        #[allow(dead_code)]
+        elapsed: Duration,
+    }
+
+    #[test]
+    fn test_negative_duration() {
+        let elapsed = Series::new("elapsed", [0i64, -1])
+            .cast(&DataType::Duration(TimeUnit::Milliseconds))
+            .unwrap();
+        let df = DataFrame::new(vec![elapsed]).unwrap();
+
+        match Elapsed::iter_df(&df).and_then(Iterator::collect::<Result<Vec<_>, _>>) {
+            Err(Error::InvalidValue { column, row, .. }) => {
+                assert_eq!(column, "elapsed");
+                assert_eq!(row, 1);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
+
+mod own_types {
+    use polars::prelude::*;
+    use polars_derive::{FromDataFrame, IntoDataFrame};
+
+    use crate::common::{assert_schema, round_trip};
+
+    /// A type called `SystemTime` isn't taken for `std::time::SystemTime`,
+    /// whether the feature is enabled or not, so it is stored through
+    /// IntoColumn.
+    #[derive(IntoDataFrame, FromDataFrame, Clone, Copy, PartialEq, Debug)]
+    #[df(transparent)]
+    struct SystemTime(u64);
+
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    struct Request {
+        sent: SystemTime,
+    }
+
+    #[test]
+    fn test_own_type() {
+        assert_schema::<Request>(&[("sent", DataType::UInt64)]);
+        round_trip(&[Request {
+            sent: SystemTime(1),
+        }]);
+    }
+}
@@ -0,0 +1,146 @@
+use polars::export::chrono::{Duration, NaiveDate, NaiveTime};
+use polars::prelude::*;
+use polars_derive::{Error, FromDataFrame, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Shift {
+    day: NaiveDate,
+    start: NaiveTime,
+    length: Duration,
+    #[df(time_unit = "ns")]
+    breaks: Vec<Duration>,
+    holidays: Option<Vec<NaiveDate>>,
+    end: Option<NaiveTime>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Length {
+    length: Duration,
+}
+
+#[derive(IntoDataFrame, Clone, PartialEq, Debug)]
+struct PreciseLength {
+    #[df(time_unit = "ns")]
+    length: Option<Duration>,
+}
+
+#[derive(FromDataFrame, Debug)]
+struct Start {
+    #[allow(dead_code)]
+    start: NaiveTime,
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2023, 5, day).unwrap()
+}
+
+fn time(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_milli_opt(hour, 15, 30, 250).unwrap()
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Shift>(&[
+        ("day", DataType::Date),
+        ("start", DataType::Time),
+        ("length", DataType::Duration(TimeUnit::Milliseconds)),
+        (
+            "breaks",
+            DataType::List(Box::new(DataType::Duration(TimeUnit::Nanoseconds))),
+        ),
+        ("holidays", DataType::List(Box::new(DataType::Date))),
+        ("end", DataType::Time),
+    ]);
+}
+
+#[test]
+fn test_physical_values() {
+    // values before the UNIX epoch, at midnight, and negative durations
+    let df = round_trip(&[Shift {
+        day: NaiveDate::from_ymd_opt(1969, 12, 31).unwrap(),
+        start: NaiveTime::MIN,
+        length: Duration::milliseconds(-1500),
+        breaks: vec![Duration::nanoseconds(1)],
+        holidays: Some(vec![date(2)]),
+        end: Some(time(16)),
+    }]);
+
+    let day = df.column("day").unwrap().date().unwrap();
+    assert_eq!(day.get(0), Some(-1));
+    let start = df.column("start").unwrap().time().unwrap();
+    assert_eq!(start.get(0), Some(0));
+    let length = df.column("length").unwrap().duration().unwrap();
+    assert_eq!(length.get(0), Some(-1500));
+    let end = df.column("end").unwrap().time().unwrap();
+    assert_eq!(end.get(0), Some(58_530_250_000_000));
+}
+
+#[test]
+fn test_read_column_time_unit() {
+    let length = Series::new("length", [1_500i64])
+        .cast(&DataType::Duration(TimeUnit::Microseconds))
+        .unwrap();
+    let df = DataFrame::new(vec![length]).unwrap();
+
+    assert_eq!(
+        Length::from_df(&df).unwrap(),
+        [Length {
+            length: Duration::microseconds(1_500)
+        }]
+    );
+}
+
+#[test]
+fn test_duration_overflow() {
+    // about 292 years fit in 64 bits of nanoseconds
+    let long = Duration::days(300 * 365);
+
+    let rows = [
+        PreciseLength { length: None },
+        PreciseLength { length: Some(long) },
+    ];
+    let err = PreciseLength::to_df(&rows).unwrap_err();
+    assert!(err.to_string().contains("field length in row 1"), "{err}");
+
+    let rows = [Shift {
+        day: date(1),
+        start: time(8),
+        length: long,
+        breaks: vec![Duration::minutes(15), long],
+        holidays: None,
+        end: None,
+    }];
+    let err = Shift::to_df(&rows).unwrap_err();
+    assert!(err.to_string().contains("field breaks in row 0"), "{err}");
+}
+
+#[test]
+#[should_panic(expected = "field length in row 0")]
+fn test_duration_overflow_panics() {
+    let rows = [PreciseLength {
+        length: Some(Duration::days(300 * 365)),
+    }];
+
+    let _ = PreciseLength::to_series(rows.iter());
+}
+
+#[test]
+fn test_invalid_time() {
+    // a day is 86400 seconds long
+    let start = Series::new("start", [86_400_000_000_000i64])
+        .cast(&DataType::Time)
+        .unwrap();
+    let df = DataFrame::new(vec![start]).unwrap();
+
+    match Start::iter_df(&df).and_then(Iterator::collect::<Result<Vec<_>, _>>) {
+        Err(Error::InvalidValue { column, row, .. }) => {
+            assert_eq!(column, "start");
+            assert_eq!(row, 0);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
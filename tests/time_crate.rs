@@ -0,0 +1,187 @@
+mod common;
+
+#[cfg(feature = "time")]
+mod with_time {
+    use polars::prelude::*;
+    use polars_derive::{Error, FromColumn, FromDataFrame, IntoDataFrame};
+    use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+    use crate::common::assert_schema;
+
+    /// The types of the `time` crate are only recognized through their module.
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    struct Booking {
+        created: time::OffsetDateTime,
+        #[df(timezone = "+02:00", time_unit = "ns")]
+        starts: Option<time::OffsetDateTime>,
+        day: time::Date,
+        holidays: Vec<time::Date>,
+        length: time::Duration,
+    }
+
+    fn booking(created: OffsetDateTime, day: Date) -> Booking {
+        Booking {
+            created,
+            starts: None,
+            day,
+            holidays: vec![],
+            length: time::Duration::ZERO,
+        }
+    }
+
+    fn date(year: i32, day: u8) -> Date {
+        Date::from_calendar_date(year, Month::May, day).unwrap()
+    }
+
+    #[test]
+    fn test_schema() {
+        assert_schema::<Booking>(&[
+            (
+                "created",
+                DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".to_owned())),
+            ),
+            (
+                "starts",
+                DataType::Datetime(TimeUnit::Nanoseconds, Some("+02:00".to_owned())),
+            ),
+            ("day", DataType::Date),
+            ("holidays", DataType::List(Box::new(DataType::Date))),
+            ("length", DataType::Duration(TimeUnit::Milliseconds)),
+        ]);
+    }
+
+    #[test]
+    fn test_physical_values() {
+        // values before the UNIX epoch are rounded down, like chrono does
+        let created = OffsetDateTime::UNIX_EPOCH - time::Duration::microseconds(1_500);
+        let df = Booking::to_df(&[booking(created, date(1969, 1))]).unwrap();
+
+        let timestamps = df.column("created").unwrap().datetime().unwrap();
+        assert_eq!(timestamps.get(0), Some(-2));
+        let days = df.column("day").unwrap().date().unwrap();
+        assert_eq!(days.get(0), Some(-245));
+
+        let read = Booking::from_df(&df).unwrap();
+        assert_eq!(
+            read[0].created,
+            OffsetDateTime::UNIX_EPOCH - time::Duration::milliseconds(2)
+        );
+        assert_eq!(read[0].day, date(1969, 1));
+    }
+
+    #[test]
+    fn test_column_time_zone() {
+        let at = OffsetDateTime::from_unix_timestamp(1_682_929_800).unwrap();
+        let rows = [Booking {
+            starts: Some(at),
+            ..booking(at, date(2023, 1))
+        }];
+        let df = Booking::to_df(&rows).unwrap();
+
+        // the values are read in the time zone of the column, whichever time
+        // zone they were written in
+        let read = &Booking::from_df(&df).unwrap()[0];
+        assert_eq!(read.created.offset(), UtcOffset::UTC);
+        let starts = read.starts.unwrap();
+        assert_eq!(starts, at);
+        assert_eq!(starts.offset(), UtcOffset::from_hms(2, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        // about 292 years fit in 64 bits of nanoseconds
+        let far = date(2300, 1).midnight().assume_utc();
+        let rows = [Booking {
+            starts: Some(far),
+            ..booking(far, date(2023, 1))
+        }];
+
+        let err = Booking::to_df(&rows).unwrap_err();
+        assert!(err.to_string().contains("field starts in row 0"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_time_zone() {
+        let created = Int64Chunked::new("created", [0i64])
+            .into_datetime(TimeUnit::Milliseconds, Some("Europe/Berlin".to_owned()))
+            .into_series();
+
+        // an OffsetDateTime can only be read out of an offset
+        let values = <OffsetDateTime as FromColumn>::from_column(&created).unwrap();
+        match &values[0] {
+            Err(Error::InvalidValue { column, row, .. }) => {
+                assert_eq!(column, "created");
+                assert_eq!(*row, 0);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_through_traits() {
+        let day = Int32Chunked::new("day", [-1i32]).into_date().into_series();
+
+        let values = <Date as FromColumn>::from_column(&day).unwrap();
+        let expected = Date::from_calendar_date(1969, Month::December, 31).unwrap();
+        assert_eq!(values[0].as_ref().unwrap(), &Some(expected));
+
+        // dates are cast like for chrono, so they can be read out of datetimes
+        let day = Int64Chunked::new("day", [129_600_000i64])
+            .into_datetime(TimeUnit::Milliseconds, None)
+            .into_series();
+
+        let values = <Date as FromColumn>::from_column(&day).unwrap();
+        let expected = Date::from_calendar_date(1970, Month::January, 2).unwrap();
+        assert_eq!(values[0].as_ref().unwrap(), &Some(expected));
+    }
+}
+
+mod own_types {
+    use polars::prelude::*;
+    use polars_derive::{FromDataFrame, IntoDataFrame};
+
+    use crate::common::{assert_schema, round_trip};
+
+    /// A type called `Date` isn't taken for `time::Date`, whether the feature
+    /// is enabled or not, so it is stored through IntoColumn.
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    #[df(transparent)]
+    struct Date(String);
+
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    struct Booking {
+        day: Date,
+    }
+
+    #[test]
+    fn test_own_type() {
+        assert_schema::<Booking>(&[("day", DataType::Utf8)]);
+        round_trip(&[Booking {
+            day: Date("monday".to_owned()),
+        }]);
+    }
+
+    /// Without the feature, a module called `time` is not taken for the
+    /// `time` crate.
+    #[cfg(not(feature = "time"))]
+    mod time {
+        #[derive(
+            polars_derive::IntoDataFrame, polars_derive::FromDataFrame, Clone, PartialEq, Debug,
+        )]
+        #[df(transparent)]
+        pub struct Date(pub u32);
+    }
+
+    #[cfg(not(feature = "time"))]
+    #[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+    struct Shift {
+        day: time::Date,
+    }
+
+    #[cfg(not(feature = "time"))]
+    #[test]
+    fn test_own_module() {
+        assert_schema::<Shift>(&[("day", DataType::UInt32)]);
+        round_trip(&[Shift { day: time::Date(3) }]);
+    }
+}
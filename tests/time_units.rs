@@ -0,0 +1,183 @@
+use polars::export::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use polars::prelude::*;
+use polars_derive::{Error, FromDataFrame, IntoDataFrame};
+
+use common::assert_schema;
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Reading {
+    taken: NaiveDateTime,
+    #[df(time_unit = "us")]
+    received: DateTime<Utc>,
+    #[df(time_unit = "ns")]
+    stored: Option<NaiveDateTime>,
+    #[df(time_unit = "ns")]
+    retries: Vec<DateTime<Utc>>,
+}
+
+#[derive(FromDataFrame, PartialEq, Debug)]
+struct Taken {
+    taken: NaiveDateTime,
+}
+
+/// The field is read through FromColumn instead of by the derive.
+#[derive(FromDataFrame, PartialEq, Debug)]
+struct Generic<T> {
+    taken: T,
+}
+
+#[derive(FromDataFrame, PartialEq, Debug)]
+#[df(transparent)]
+struct Stamp(NaiveDateTime);
+
+#[derive(FromDataFrame, PartialEq, Debug)]
+struct Stamped {
+    taken: Stamp,
+}
+
+fn at(nanos: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2023, 5, 1)
+        .unwrap()
+        .and_hms_nano_opt(8, 30, 0, nanos)
+        .unwrap()
+}
+
+/// Returns a DataFrame with a single `Datetime` column called `taken`, which
+/// was written by something else in `unit`.
+fn taken(unit: TimeUnit, timestamps: &[i64]) -> DataFrame {
+    let taken = Int64Chunked::new("taken", timestamps)
+        .into_datetime(unit, None)
+        .into_series();
+
+    DataFrame::new(vec![taken]).unwrap()
+}
+
+/// Timestamps of the same instant in each time unit, with as much precision
+/// as the unit has.
+fn timestamps() -> [(TimeUnit, i64, NaiveDateTime); 3] {
+    [
+        (TimeUnit::Milliseconds, 1_682_929_800_123, at(123_000_000)),
+        (
+            TimeUnit::Microseconds,
+            1_682_929_800_123_456,
+            at(123_456_000),
+        ),
+        (
+            TimeUnit::Nanoseconds,
+            1_682_929_800_123_456_789,
+            at(123_456_789),
+        ),
+    ]
+}
+
+#[test]
+fn test_schema() {
+    let utc = Some("UTC".to_owned());
+
+    assert_schema::<Reading>(&[
+        ("taken", DataType::Datetime(TimeUnit::Milliseconds, None)),
+        (
+            "received",
+            DataType::Datetime(TimeUnit::Microseconds, utc.clone()),
+        ),
+        ("stored", DataType::Datetime(TimeUnit::Nanoseconds, None)),
+        (
+            "retries",
+            DataType::List(Box::new(DataType::Datetime(TimeUnit::Nanoseconds, utc))),
+        ),
+    ]);
+}
+
+#[test]
+fn test_write_time_unit() {
+    let reading = Reading {
+        taken: at(123_456_789),
+        received: at(123_456_789).and_utc(),
+        stored: Some(at(123_456_789)),
+        retries: vec![at(123_456_789).and_utc()],
+    };
+    let df = Reading::to_df(&[reading]).unwrap();
+
+    // the precision which doesn't fit in the unit of the field is lost
+    let taken = df.column("taken").unwrap().datetime().unwrap();
+    assert_eq!(taken.get(0), Some(1_682_929_800_123));
+    let received = df.column("received").unwrap().datetime().unwrap();
+    assert_eq!(received.get(0), Some(1_682_929_800_123_456));
+    let stored = df.column("stored").unwrap().datetime().unwrap();
+    assert_eq!(stored.get(0), Some(1_682_929_800_123_456_789));
+}
+
+#[test]
+fn test_read_column_time_unit() {
+    // the field is written in milliseconds, but the column might have been
+    // written by something else in another unit
+    for (unit, timestamp, expected) in timestamps() {
+        let df = taken(unit, &[timestamp]);
+
+        assert_eq!(Taken::from_df(&df).unwrap(), [Taken { taken: expected }]);
+    }
+}
+
+#[test]
+fn test_read_column_time_unit_through_traits() {
+    for (unit, timestamp, expected) in timestamps() {
+        let df = taken(unit, &[timestamp]);
+
+        assert_eq!(
+            Generic::<NaiveDateTime>::from_df(&df).unwrap(),
+            [Generic { taken: expected }]
+        );
+        assert_eq!(
+            Generic::<Option<DateTime<Utc>>>::from_df(&df).unwrap(),
+            [Generic {
+                taken: Some(expected.and_utc())
+            }]
+        );
+        assert_eq!(
+            Stamped::from_df(&df).unwrap(),
+            [Stamped {
+                taken: Stamp(expected)
+            }]
+        );
+    }
+}
+
+#[test]
+fn test_out_of_range() {
+    // the largest timestamps in milliseconds are too far away for chrono
+    let df = taken(TimeUnit::Milliseconds, &[0, i64::MAX]);
+
+    for result in [
+        Taken::iter_df(&df).and_then(Iterator::collect::<Result<Vec<_>, _>>),
+        Generic::<NaiveDateTime>::iter_df(&df)
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| Taken { taken: row.taken })
+                    .collect()
+            }),
+    ] {
+        match result {
+            Err(Error::InvalidValue { column, row, .. }) => {
+                assert_eq!(column, "taken");
+                assert_eq!(row, 1);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_not_a_datetime() {
+    let df = DataFrame::new(vec![Series::new("taken", [1i64])]).unwrap();
+
+    match Generic::<NaiveDateTime>::iter_df(&df).map(|_| ()) {
+        Err(Error::DtypeMismatch { column, actual, .. }) => {
+            assert_eq!(column, "taken");
+            assert_eq!(actual, DataType::Int64);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
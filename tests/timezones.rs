@@ -0,0 +1,211 @@
+use chrono_tz::Tz;
+use polars::export::chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use polars::prelude::*;
+use polars_derive::{Error, FromColumn, FromDataFrame, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Event {
+    created: DateTime<Utc>,
+    #[df(timezone = "+02:00")]
+    offset: DateTime<FixedOffset>,
+    #[df(timezone = "Europe/Berlin")]
+    local: DateTime<Tz>,
+    history: Vec<DateTime<Utc>>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Precise {
+    #[df(time_unit = "ns")]
+    created: Option<DateTime<Utc>>,
+    #[df(time_unit = "ns")]
+    history: Vec<DateTime<Utc>>,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Explicit {
+    #[df(dtype = Datetime(Nanoseconds))]
+    created: DateTime<Utc>,
+}
+
+#[derive(FromDataFrame, Clone, PartialEq, Debug)]
+struct Raw {
+    #[df(deserialize_with = from_millis)]
+    created: DateTime<Utc>,
+}
+
+fn from_millis(millis: i64) -> Result<DateTime<Utc>, String> {
+    // the timestamps are given as they are stored, in milliseconds
+    Utc.timestamp_millis_opt(millis * 2)
+        .single()
+        .ok_or_else(|| "invalid timestamp".to_owned())
+}
+
+fn utc(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 5, 1, hour, 30, 0).unwrap()
+}
+
+/// Returns a `Datetime` column called `name` in milliseconds, in the time
+/// zone `tz`.
+fn datetimes(name: &str, values: &[DateTime<Utc>], tz: Option<&str>) -> Series {
+    let millis: Vec<_> = values.iter().map(DateTime::timestamp_millis).collect();
+
+    Int64Chunked::new(name, millis)
+        .into_datetime(TimeUnit::Milliseconds, tz.map(str::to_owned))
+        .into_series()
+}
+
+#[test]
+fn test_schema() {
+    let datetime = |tz: &str| DataType::Datetime(TimeUnit::Milliseconds, Some(tz.to_owned()));
+
+    assert_schema::<Event>(&[
+        ("created", datetime("UTC")),
+        ("offset", datetime("+02:00")),
+        ("local", datetime("Europe/Berlin")),
+        ("history", DataType::List(Box::new(datetime("UTC")))),
+    ]);
+}
+
+#[test]
+fn test_column_time_zone() {
+    // the values are read in the time zone of the column, not the one which
+    // is set on the field
+    let df = DataFrame::new(vec![
+        datetimes("created", &[utc(8)], Some("Asia/Tokyo")),
+        datetimes("offset", &[utc(8)], Some("-05:00")),
+        datetimes("local", &[utc(8)], Some("America/New_York")),
+        Series::new("history", [datetimes("", &[utc(9)], Some("+01:00"))]),
+    ])
+    .unwrap();
+
+    let event = &Event::from_df(&df).unwrap()[0];
+    assert_eq!(event.created, utc(8));
+    assert_eq!(event.offset.to_rfc3339(), "2023-05-01T03:30:00-05:00");
+    assert_eq!(event.local.timezone(), Tz::America__New_York);
+    assert_eq!(event.local.to_rfc3339(), "2023-05-01T04:30:00-04:00");
+    assert_eq!(event.history, vec![utc(9)]);
+}
+
+#[test]
+fn test_column_without_time_zone() {
+    let df = DataFrame::new(vec![
+        datetimes("created", &[utc(8)], None),
+        datetimes("offset", &[utc(8)], None),
+        datetimes("local", &[utc(8)], None),
+        Series::new("history", [datetimes("", &[], None)]),
+    ])
+    .unwrap();
+
+    let event = &Event::from_df(&df).unwrap()[0];
+    assert_eq!(event.offset.to_rfc3339(), "2023-05-01T08:30:00+00:00");
+    assert_eq!(event.local.timezone(), Tz::UTC);
+}
+
+#[test]
+fn test_invalid_time_zone() {
+    let df = DataFrame::new(vec![
+        datetimes("created", &[utc(8), utc(9)], None),
+        datetimes("offset", &[utc(8), utc(9)], Some("Europe/Berlin")),
+        datetimes("local", &[utc(8), utc(9)], Some("Mars/Olympus_Mons")),
+        Series::new(
+            "history",
+            [datetimes("", &[], None), datetimes("", &[], None)],
+        ),
+    ])
+    .unwrap();
+
+    // a FixedOffset can only be read out of an offset
+    let errors = Event::from_df_lenient(&df).unwrap().1;
+    assert_eq!(errors.len(), 2);
+    match &errors[1].error {
+        Error::InvalidValue { column, row, .. } => {
+            assert_eq!(column, "offset");
+            assert_eq!(*row, 1);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn test_out_of_range_nanoseconds() {
+    let far = Utc.with_ymd_and_hms(2300, 1, 1, 0, 0, 0).unwrap();
+    let rows = vec![
+        Precise {
+            created: Some(utc(8)),
+            history: vec![],
+        },
+        Precise {
+            created: Some(far),
+            history: vec![],
+        },
+    ];
+
+    let err = Precise::to_df(&rows).unwrap_err();
+    assert!(err.to_string().contains("field created in row 1"), "{err}");
+
+    // items of lists refer to the row of the list
+    let rows = vec![
+        Precise {
+            created: None,
+            history: vec![],
+        },
+        Precise {
+            created: None,
+            history: vec![utc(8), far],
+        },
+    ];
+    let err = Precise::into_df(rows.into_iter()).unwrap_err();
+    assert!(err.to_string().contains("field history in row 1"), "{err}");
+}
+
+#[test]
+fn test_explicit_dtype() {
+    // the time zone of the field is kept when only the time unit is given
+    assert_schema::<Explicit>(&[(
+        "created",
+        DataType::Datetime(TimeUnit::Nanoseconds, Some("UTC".to_owned())),
+    )]);
+
+    round_trip(&[Explicit { created: utc(8) }]);
+}
+
+#[test]
+fn test_deserialize_with_raw_timestamps() {
+    let df = DataFrame::new(vec![datetimes("created", &[utc(8)], Some("UTC"))]).unwrap();
+
+    let raw = Raw::from_df(&df).unwrap();
+    assert_eq!(
+        raw[0].created.timestamp_millis(),
+        utc(8).timestamp_millis() * 2
+    );
+}
+
+#[test]
+fn test_through_traits() {
+    let series = datetimes("offset", &[utc(8)], Some("+02:00"));
+
+    let values = <DateTime<FixedOffset> as FromColumn>::from_column(&series).unwrap();
+    let value = values[0].as_ref().unwrap().unwrap();
+    assert_eq!(value.to_rfc3339(), "2023-05-01T10:30:00+02:00");
+
+    let values = <DateTime<Utc> as FromColumn>::from_column(&series).unwrap();
+    assert_eq!(values[0].as_ref().unwrap(), &Some(utc(8)));
+
+    // naive datetimes ignore the time zone of the column
+    let values = <NaiveDateTime as FromColumn>::from_column(&series).unwrap();
+    assert_eq!(values[0].as_ref().unwrap(), &Some(utc(8).naive_utc()));
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn test_chrono_tz_through_traits() {
+    let series = datetimes("local", &[utc(8)], Some("Europe/Berlin"));
+
+    let values = <DateTime<Tz> as FromColumn>::from_column(&series).unwrap();
+    let value = values[0].as_ref().unwrap().unwrap();
+    assert_eq!(value.to_rfc3339(), "2023-05-01T10:30:00+02:00");
+}
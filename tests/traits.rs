@@ -0,0 +1,250 @@
+use polars::prelude::*;
+use polars_derive::{Error, FromColumn, FromDataFrame, IntoColumn, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+type UserId = u64;
+
+/// A type from "another crate", stored as a string through a manual impl.
+#[derive(Clone, PartialEq, Debug)]
+struct Email {
+    user: String,
+    domain: String,
+}
+
+impl IntoColumn for Email {
+    fn dtype() -> DataType {
+        DataType::Utf8
+    }
+
+    fn into_column(name: &str, values: Vec<Option<Self>>) -> Series {
+        Self::to_column(name, values.iter().map(Option::as_ref).collect())
+    }
+
+    fn to_column(name: &str, values: Vec<Option<&Self>>) -> Series {
+        let values: Vec<_> = values
+            .into_iter()
+            .map(|value| value.map(|value| format!("{}@{}", value.user, value.domain)))
+            .collect();
+
+        Series::new(name, values)
+    }
+}
+
+impl FromColumn for Email {
+    fn from_column(series: &Series) -> Result<Vec<Result<Option<Self>, Error>>, Error> {
+        let values = series.utf8().map_err(|_| Error::DtypeMismatch {
+            column: series.name().to_owned(),
+            expected: DataType::Utf8,
+            actual: series.dtype().clone(),
+        })?;
+
+        Ok(values
+            .into_iter()
+            .enumerate()
+            .map(|(row, value)| {
+                value
+                    .map(|value| match value.split_once('@') {
+                        Some((user, domain)) => Ok(Email {
+                            user: user.to_owned(),
+                            domain: domain.to_owned(),
+                        }),
+                        None => Err(Error::InvalidValue {
+                            column: series.name().to_owned(),
+                            row,
+                            source: format!("invalid email: {value}").into(),
+                        }),
+                    })
+                    .transpose()
+            })
+            .collect())
+    }
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Tag {
+    key: String,
+    weight: u32,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Account {
+    id: UserId,
+    name: std::string::String,
+    email: Email,
+    backup: Option<Email>,
+    followers: Vec<UserId>,
+    tags: Vec<Tag>,
+    primary: (Email, u32),
+}
+
+fn accounts() -> Vec<Account> {
+    vec![
+        Account {
+            id: 1,
+            name: "alice".to_owned(),
+            email: Email {
+                user: "alice".to_owned(),
+                domain: "example.com".to_owned(),
+            },
+            backup: None,
+            followers: vec![2, 3],
+            tags: vec![Tag {
+                key: "admin".to_owned(),
+                weight: 10,
+            }],
+            primary: (
+                Email {
+                    user: "a".to_owned(),
+                    domain: "example.org".to_owned(),
+                },
+                1,
+            ),
+        },
+        Account {
+            id: 2,
+            name: "bob".to_owned(),
+            email: Email {
+                user: "bob".to_owned(),
+                domain: "example.com".to_owned(),
+            },
+            backup: Some(Email {
+                user: "bob".to_owned(),
+                domain: "example.net".to_owned(),
+            }),
+            followers: vec![],
+            tags: vec![
+                Tag {
+                    key: "a".to_owned(),
+                    weight: 1,
+                },
+                Tag {
+                    key: "b".to_owned(),
+                    weight: 2,
+                },
+            ],
+            primary: (
+                Email {
+                    user: "b".to_owned(),
+                    domain: "example.org".to_owned(),
+                },
+                2,
+            ),
+        },
+    ]
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Account>(&[
+        ("id", DataType::UInt64),
+        ("name", DataType::Utf8),
+        ("email", DataType::Utf8),
+        ("backup", DataType::Utf8),
+        ("followers", DataType::List(Box::new(DataType::UInt64))),
+        (
+            "tags",
+            DataType::List(Box::new(DataType::Struct(vec![
+                Field::new("key", DataType::Utf8),
+                Field::new("weight", DataType::UInt32),
+            ]))),
+        ),
+        (
+            "primary",
+            DataType::Struct(vec![
+                Field::new("0", DataType::Utf8),
+                Field::new("1", DataType::UInt32),
+            ]),
+        ),
+    ]);
+}
+
+#[test]
+fn test_round_trip() {
+    let df = round_trip(&accounts());
+
+    let email = df.column("email").unwrap().utf8().unwrap();
+    assert_eq!(email.get(0), Some("alice@example.com"));
+}
+
+#[test]
+fn test_read_error() {
+    let df = Account::into_df(accounts().into_iter()).unwrap();
+    let mut df = df.drop("email").unwrap();
+    df.with_column(Series::new("email", ["alice", "bob"]))
+        .unwrap();
+
+    assert!(Account::from_df(&df).is_err());
+}
+
+/// A type of our own with the same name as the one from chrono, which is
+/// stored through its own impls when it's named through its module.
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(transparent)]
+struct Duration(String);
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Timeout {
+    limit: self::Duration,
+    retries: Vec<self::Duration>,
+}
+
+#[test]
+fn test_shadowed_name() {
+    assert_schema::<Timeout>(&[
+        ("limit", DataType::Utf8),
+        ("retries", DataType::List(Box::new(DataType::Utf8))),
+    ]);
+
+    round_trip(&[Timeout {
+        limit: Duration("5s".to_owned()),
+        retries: vec![Duration("1s".to_owned()), Duration("2s".to_owned())],
+    }]);
+}
+
+#[cfg(feature = "dtype-u8")]
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Blob<T> {
+    data: T,
+    direct: Vec<u8>,
+}
+
+#[cfg(feature = "dtype-u8")]
+#[test]
+fn test_bytes_through_traits() {
+    // Vec<u8> is stored as Binary whether the derive recognizes it or not
+    assert_schema::<Blob<Vec<u8>>>(&[("data", DataType::Binary), ("direct", DataType::Binary)]);
+
+    round_trip(&[
+        Blob {
+            data: vec![1, 2, 3],
+            direct: vec![4, 5],
+        },
+        Blob {
+            data: vec![],
+            direct: vec![6],
+        },
+    ]);
+}
+
+#[cfg(feature = "dtype-u8")]
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct NullableBlob<T> {
+    data: T,
+    direct: Vec<Option<u8>>,
+}
+
+#[cfg(feature = "dtype-u8")]
+#[test]
+fn test_nullable_bytes_through_traits() {
+    // bytes which can be null are stored as a list, which can hold nulls
+    let list = DataType::List(Box::new(DataType::UInt8));
+    assert_schema::<NullableBlob<Vec<Option<u8>>>>(&[("data", list.clone()), ("direct", list)]);
+
+    round_trip(&[NullableBlob {
+        data: vec![Some(1), None, Some(3)],
+        direct: vec![None, Some(4)],
+    }]);
+}
@@ -0,0 +1,116 @@
+use polars::prelude::*;
+use polars_derive::{FromColumn, FromDataFrame, IntoColumn, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, Copy, PartialEq, Debug)]
+#[df(transparent)]
+struct UserId(u64);
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(transparent)]
+struct Name {
+    value: String,
+}
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+#[df(transparent)]
+struct Labels<T>(Vec<T>);
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct User {
+    id: UserId,
+    name: Name,
+    manager: Option<UserId>,
+    reports: Vec<UserId>,
+    labels: Labels<String>,
+}
+
+fn users() -> Vec<User> {
+    vec![
+        User {
+            id: UserId(1),
+            name: Name {
+                value: "alice".to_owned(),
+            },
+            manager: None,
+            reports: vec![UserId(2)],
+            labels: Labels(vec!["admin".to_owned()]),
+        },
+        User {
+            id: UserId(2),
+            name: Name {
+                value: "bob".to_owned(),
+            },
+            manager: Some(UserId(1)),
+            reports: vec![],
+            labels: Labels(vec![]),
+        },
+    ]
+}
+
+#[test]
+fn test_dtype() {
+    assert_eq!(UserId::dtype(), DataType::UInt64);
+    assert_eq!(Name::dtype(), DataType::Utf8);
+    assert_eq!(
+        Labels::<String>::dtype(),
+        DataType::List(Box::new(DataType::Utf8))
+    );
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<User>(&[
+        ("id", DataType::UInt64),
+        ("name", DataType::Utf8),
+        ("manager", DataType::UInt64),
+        ("reports", DataType::List(Box::new(DataType::UInt64))),
+        ("labels", DataType::List(Box::new(DataType::Utf8))),
+    ]);
+}
+
+#[test]
+fn test_round_trip() {
+    let df = round_trip(&users());
+
+    let id = df.column("id").unwrap().u64().unwrap();
+    assert_eq!(id.get(1), Some(2));
+}
+
+#[test]
+fn test_read() {
+    let df = df! {
+        "id" => [7u64],
+        "name" => ["carol"],
+        "manager" => [None::<u64>],
+    }
+    .unwrap();
+    let ids = UserId::from_column(df.column("id").unwrap())
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(ids, [Some(UserId(7))]);
+
+    let names = Name::from_column(df.column("name").unwrap())
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        names,
+        [Some(Name {
+            value: "carol".to_owned()
+        })]
+    );
+
+    let managers = UserId::from_column(df.column("manager").unwrap())
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(managers, [None]);
+}
@@ -0,0 +1,67 @@
+use polars::prelude::*;
+use polars_derive::{FromDataFrame, IntoDataFrame};
+
+use common::{assert_schema, round_trip};
+
+mod common;
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Pair(String, Option<u32>);
+
+#[derive(IntoDataFrame, FromDataFrame, Clone, PartialEq, Debug)]
+struct Sample(
+    #[df(rename = "label")] String,
+    f64,
+    #[df(alias = "extra")] Vec<i64>,
+    Pair,
+);
+
+fn samples() -> Vec<Sample> {
+    vec![
+        Sample(
+            "a".to_owned(),
+            1.5,
+            vec![1, 2],
+            Pair("x".to_owned(), Some(1)),
+        ),
+        Sample("b".to_owned(), -0.5, vec![], Pair("y".to_owned(), None)),
+    ]
+}
+
+#[test]
+fn test_schema() {
+    assert_schema::<Pair>(&[("column_0", DataType::Utf8), ("column_1", DataType::UInt32)]);
+
+    assert_schema::<Sample>(&[
+        ("label", DataType::Utf8),
+        ("column_1", DataType::Float64),
+        ("column_2", DataType::List(Box::new(DataType::Int64))),
+        (
+            "column_3",
+            DataType::Struct(vec![
+                Field::new("column_0", DataType::Utf8),
+                Field::new("column_1", DataType::UInt32),
+            ]),
+        ),
+    ]);
+}
+
+#[test]
+fn test_round_trip() {
+    round_trip(&samples());
+}
+
+#[test]
+fn test_read() {
+    let df = df! {
+        "column_0" => ["a", "b"],
+        "column_1" => [Some(1u32), None],
+    }
+    .unwrap();
+
+    let list = Pair::from_df(&df).unwrap();
+    assert_eq!(
+        list,
+        [Pair("a".to_owned(), Some(1)), Pair("b".to_owned(), None)]
+    );
+}